@@ -0,0 +1 @@
+DROP INDEX repo_func_mat_view_text_search_index;
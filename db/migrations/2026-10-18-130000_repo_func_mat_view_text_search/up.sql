@@ -0,0 +1,7 @@
+-- full text search over function names and documentation,
+-- queries must use the same expression for the index to be used
+CREATE INDEX repo_func_mat_view_text_search_index
+  ON repository_function_mat_view USING GIN ((
+    setweight(to_tsvector('english', func_name), 'A') ||
+    setweight(to_tsvector('english', coalesce(func_documentation, '')), 'B')
+  ));
@@ -0,0 +1,29 @@
+-- drop materialized view
+DROP MATERIALIZED VIEW repository_function_mat_view CASCADE;
+
+-- restore the constraint without module
+ALTER TABLE "functions"
+DROP CONSTRAINT function_repo,
+ADD CONSTRAINT function_repo UNIQUE (repo_id, name, type_signature);
+
+-- remove module column
+ALTER TABLE "functions" DROP COLUMN module;
+
+-- recreate old materialized view
+CREATE MATERIALIZED VIEW repository_function_mat_view AS
+  SELECT r.id as repo_id, r.name as repo_name, r.url as repo_url, r.ver as repo_version,
+         f.id as func_id, f.name as func_name, f.type_signature as func_type_sig,
+         f.documentation as func_documentation
+  FROM repositories AS r, functions AS f
+  WHERE r.id = f.repo_id;
+
+-- crate index on function id
+CREATE UNIQUE INDEX repo_func_mat_view_func_id_index
+  ON repository_function_mat_view (func_id);
+
+-- recreate full text search index
+CREATE INDEX repo_func_mat_view_text_search_index
+  ON repository_function_mat_view USING GIN ((
+    setweight(to_tsvector('english', func_name), 'A') ||
+    setweight(to_tsvector('english', coalesce(func_documentation, '')), 'B')
+  ));
@@ -0,0 +1,30 @@
+-- add module field
+ALTER TABLE "functions"
+ADD COLUMN module TEXT NOT NULL DEFAULT '';
+
+-- functions with the same name and signature may be defined in different modules
+ALTER TABLE "functions"
+DROP CONSTRAINT function_repo,
+ADD CONSTRAINT function_repo UNIQUE (repo_id, module, name, type_signature);
+
+-- drop old materialized view
+DROP MATERIALIZED VIEW repository_function_mat_view CASCADE;
+
+-- crate new materialized view
+CREATE MATERIALIZED VIEW repository_function_mat_view AS
+  SELECT r.id as repo_id, r.name as repo_name, r.url as repo_url, r.ver as repo_version,
+         f.id as func_id, f.name as func_name, f.type_signature as func_type_sig,
+         f.documentation as func_documentation, f.module as func_module
+  FROM repositories AS r, functions AS f
+  WHERE r.id = f.repo_id;
+
+-- crate index on function id
+CREATE UNIQUE INDEX repo_func_mat_view_func_id_index
+  ON repository_function_mat_view (func_id);
+
+-- recreate full text search index
+CREATE INDEX repo_func_mat_view_text_search_index
+  ON repository_function_mat_view USING GIN ((
+    setweight(to_tsvector('english', func_name), 'A') ||
+    setweight(to_tsvector('english', coalesce(func_documentation, '')), 'B')
+  ));
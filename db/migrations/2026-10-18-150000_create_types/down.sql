@@ -0,0 +1,3 @@
+ALTER TABLE "types" DROP CONSTRAINT IF EXISTS "types_fk0";
+
+DROP TABLE IF EXISTS "types";
@@ -0,0 +1,20 @@
+CREATE TABLE "types" (
+  "id" bigserial NOT NULL,
+  "repo_id" serial NOT NULL,
+  "name" TEXT NOT NULL,
+  "module" TEXT NOT NULL,
+  "kind" TEXT NOT NULL,
+  "parameters" TEXT[] NOT NULL,
+  "definition" TEXT NOT NULL,
+  "constructors" TEXT[] NOT NULL,
+  "documentation" TEXT,
+  CONSTRAINT types_pk PRIMARY KEY ("id"),
+  CONSTRAINT type_repo UNIQUE (repo_id, module, name)
+) WITH (
+  OIDS=FALSE
+);
+
+ALTER TABLE "types" ADD CONSTRAINT "types_fk0" FOREIGN KEY ("repo_id") REFERENCES "repositories"("id");
+
+-- types are looked up by name
+CREATE INDEX types_name_index ON types (name);
@@ -0,0 +1,2 @@
+-- remove expanded type signature column
+ALTER TABLE "functions" DROP COLUMN expanded_type_signature;
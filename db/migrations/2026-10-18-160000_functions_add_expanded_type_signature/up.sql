@@ -0,0 +1,6 @@
+-- add the type signature with type aliases expanded
+ALTER TABLE "functions"
+ADD COLUMN expanded_type_signature TEXT NOT NULL DEFAULT '';
+
+-- existing functions are stored without their aliases expanded
+UPDATE "functions" SET expanded_type_signature = type_signature;
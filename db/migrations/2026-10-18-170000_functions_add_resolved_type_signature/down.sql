@@ -0,0 +1,2 @@
+-- remove resolved type signature column
+ALTER TABLE "functions" DROP COLUMN resolved_type_signature;
@@ -0,0 +1,6 @@
+-- add the type signature with type names resolved to the module they are defined in
+ALTER TABLE "functions"
+ADD COLUMN resolved_type_signature TEXT NOT NULL DEFAULT '';
+
+-- existing functions are stored without their type names resolved
+UPDATE "functions" SET resolved_type_signature = type_signature;
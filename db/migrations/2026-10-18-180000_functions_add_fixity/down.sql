@@ -0,0 +1,2 @@
+-- remove operator fixity columns
+ALTER TABLE "functions" DROP COLUMN associativity, DROP COLUMN precedence;
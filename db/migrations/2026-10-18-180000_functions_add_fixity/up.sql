@@ -0,0 +1,4 @@
+-- add the associativity and precedence of operators, which are null for other functions
+ALTER TABLE "functions"
+ADD COLUMN associativity TEXT,
+ADD COLUMN precedence INTEGER;
@@ -0,0 +1,2 @@
+-- remove the port column
+ALTER TABLE "functions" DROP COLUMN port;
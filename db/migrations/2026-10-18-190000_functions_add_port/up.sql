@@ -0,0 +1,2 @@
+-- add whether a function is a port, `port send : String -> Cmd msg`
+ALTER TABLE "functions" ADD COLUMN port BOOLEAN NOT NULL DEFAULT FALSE;
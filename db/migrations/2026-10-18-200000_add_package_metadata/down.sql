@@ -0,0 +1,5 @@
+ALTER TABLE "dependencies" DROP CONSTRAINT IF EXISTS "dependencies_fk0";
+
+DROP TABLE IF EXISTS "dependencies";
+
+ALTER TABLE "repositories" DROP COLUMN summary, DROP COLUMN license, DROP COLUMN elm_version;
@@ -0,0 +1,18 @@
+-- the metadata in the elm.json of each package
+ALTER TABLE "repositories"
+ADD COLUMN summary TEXT,
+ADD COLUMN license TEXT,
+ADD COLUMN elm_version TEXT;
+
+CREATE TABLE "dependencies" (
+  "id" serial NOT NULL,
+  "repo_id" INTEGER NOT NULL,
+  "package" TEXT NOT NULL,
+  "version_constraint" TEXT NOT NULL,
+  CONSTRAINT dependencies_pk PRIMARY KEY ("id"),
+  CONSTRAINT dependency_repo UNIQUE (repo_id, package)
+) WITH (
+  OIDS=FALSE
+);
+
+ALTER TABLE "dependencies" ADD CONSTRAINT "dependencies_fk0" FOREIGN KEY ("repo_id") REFERENCES "repositories"("id");
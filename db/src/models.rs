@@ -8,6 +8,12 @@ pub struct Repository {
     pub name: String,
     pub url: String,
     pub ver: String,
+    /// the summary of the package from its elm.json
+    pub summary: Option<String>,
+    /// the license of the package, e.g. `BSD-3-Clause`
+    pub license: Option<String>,
+    /// the versions of elm the package works with, e.g. `0.19.0 <= v < 0.20.0`
+    pub elm_version: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset, Debug)]
@@ -18,12 +24,44 @@ pub struct NewRepository<'a> {
     pub ver: &'a str,
 }
 
+/// a package another package depends on, from the dependencies in its elm.json
+#[derive(Queryable, Clone, Debug, Serialize)]
+pub struct Dependency {
+    pub id: i32,
+    pub repo_id: i32,
+    /// the name of the package depended on, e.g. `elm/core`
+    pub package: String,
+    /// the versions of the package which may be used, e.g. `1.0.0 <= v < 2.0.0`
+    pub version_constraint: String,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "dependencies"]
+pub struct NewDependency<'a> {
+    pub repo_id: i32,
+    pub package: &'a str,
+    pub version_constraint: &'a str,
+}
+
 #[derive(Queryable, Clone, Debug, Serialize, Deserialize)]
 pub struct Function {
     pub id: i64,
     pub repo_id: i32,
     pub name: String,
     pub type_signature: String,
+    pub documentation: Option<String>,
+    pub module: String,
+    /// resolved_type_signature with type aliases replaced by the types they stand for
+    pub expanded_type_signature: String,
+    /// type_signature with every type qualified by the module it is defined in,
+    /// e.g. `Decode.Decoder a` is `Json.Decode.Decoder a`
+    pub resolved_type_signature: String,
+    /// `left`, `right` or `non` for operators, e.g. `|>` is left associative
+    pub associativity: Option<String>,
+    /// how tightly an operator binds, operators with higher precedence bind tighter
+    pub precedence: Option<i32>,
+    /// true for ports, which send commands to or subscribe to messages from javascript
+    pub port: bool,
 }
 
 impl PartialEq for Function {
@@ -38,6 +76,43 @@ pub struct NewFunction<'a> {
     pub repo_id: i32,
     pub type_signature: String,
     pub name: &'a str,
+    pub documentation: Option<&'a str>,
+    pub module: &'a str,
+    pub expanded_type_signature: String,
+    pub resolved_type_signature: String,
+    pub associativity: Option<String>,
+    pub precedence: Option<i32>,
+    pub port: bool,
+}
+
+/// a custom type or type alias
+#[derive(Queryable, Clone, Debug, Serialize)]
+pub struct Type {
+    pub id: i64,
+    pub repo_id: i32,
+    pub name: String,
+    pub module: String,
+    /// `alias` or `custom`
+    pub kind: String,
+    pub parameters: Vec<String>,
+    /// the aliased type, or the constructors separated by `|`
+    pub definition: String,
+    /// the constructors of a custom type applied to their arguments, e.g. `Just a`
+    pub constructors: Vec<String>,
+    pub documentation: Option<String>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "types"]
+pub struct NewType<'a> {
+    pub repo_id: i32,
+    pub name: &'a str,
+    pub module: &'a str,
+    pub kind: &'a str,
+    pub parameters: &'a [String],
+    pub definition: String,
+    pub constructors: Vec<String>,
+    pub documentation: Option<&'a str>,
 }
 
 #[derive(Serialize, Queryable, QueryableByName)]
@@ -50,4 +125,44 @@ pub struct FunctionWithRepo {
     pub func_id: i64,
     pub func_name: String,
     pub func_type_sig: String,
+    pub func_documentation: Option<String>,
+    pub func_module: String,
+}
+
+impl FunctionWithRepo {
+    /// the name of the function qualified by its module, e.g. `Dict.get`, operators are
+    /// wrapped in parentheses, e.g. `Basics.(|>)`
+    pub fn qualified_name(&self) -> String {
+        let is_operator = self
+            .func_name
+            .chars()
+            .next()
+            .map_or(false, |c| !c.is_alphanumeric() && c != '_');
+        if self.func_module.is_empty() {
+            self.func_name.clone()
+        } else if is_operator {
+            format!("{}.({})", self.func_module, self.func_name)
+        } else {
+            format!("{}.{}", self.func_module, self.func_name)
+        }
+    }
+}
+
+/// a function matching a full text search, along with how well it matches
+#[derive(Serialize, QueryableByName)]
+pub struct FunctionTextMatch {
+    #[diesel(embed)]
+    pub function: FunctionWithRepo,
+    #[sql_type = "diesel::sql_types::Float"]
+    pub rank: f32,
+    /// fragments of the documentation with matching words highlighted
+    #[sql_type = "diesel::sql_types::Text"]
+    pub snippet: String,
+}
+
+/// the number of rows matching a raw sql query
+#[derive(QueryableByName)]
+pub struct Count {
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub count: i64,
 }
@@ -1,9 +1,25 @@
+table! {
+    dependencies (id) {
+        id -> Int4,
+        repo_id -> Int4,
+        package -> Text,
+        version_constraint -> Text,
+    }
+}
+
 table! {
     functions (id) {
         id -> Int8,
         repo_id -> Int4,
         name -> Text,
         type_signature -> Text,
+        documentation -> Nullable<Text>,
+        module -> Text,
+        expanded_type_signature -> Text,
+        resolved_type_signature -> Text,
+        associativity -> Nullable<Text>,
+        precedence -> Nullable<Int4>,
+        port -> Bool,
     }
 }
 
@@ -13,12 +29,31 @@ table! {
         name -> Text,
         url -> Text,
         ver -> Text,
+        summary -> Nullable<Text>,
+        license -> Nullable<Text>,
+        elm_version -> Nullable<Text>,
+    }
+}
+
+table! {
+    types (id) {
+        id -> Int8,
+        repo_id -> Int4,
+        name -> Text,
+        module -> Text,
+        kind -> Text,
+        parameters -> Array<Text>,
+        definition -> Text,
+        constructors -> Array<Text>,
+        documentation -> Nullable<Text>,
     }
 }
 
+joinable!(dependencies -> repositories (repo_id));
 joinable!(functions -> repositories (repo_id));
+joinable!(types -> repositories (repo_id));
 
-allow_tables_to_appear_in_same_query!(functions, repositories,);
+allow_tables_to_appear_in_same_query!(dependencies, functions, repositories, types,);
 
 table! {
     repository_function_mat_view (repo_id, func_id) {
@@ -29,5 +64,7 @@ table! {
         func_id -> Int8,
         func_name -> Text,
         func_type_sig -> Text,
+        func_documentation -> Nullable<Text>,
+        func_module -> Text,
     }
 }
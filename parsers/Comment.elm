@@ -0,0 +1,35 @@
+module Comment exposing (..)
+
+{-| Parsers for the comments and whitespace between declarations.
+
+@docs commentStart, commentEnd, spaces
+
+-}
+
+import Parser exposing (Parser, chompWhile, symbol)
+
+
+{-| Parse the start of a multi-line comment.
+-}
+commentStart : Parser ()
+commentStart =
+    symbol "{-"
+
+
+{-| Parse the end of a multi-line comment.
+-}
+commentEnd : Parser ()
+commentEnd =
+    symbol "-}"
+
+
+{-| Whitespace, including newlines.
+-}
+spaces : Parser ()
+spaces =
+    let
+        isSpace : Char -> Bool
+        isSpace c =
+            c == ' ' || c == '\n' || c == '\r'
+    in
+    chompWhile isSpace
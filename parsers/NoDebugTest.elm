@@ -0,0 +1,34 @@
+module NoDebugTest exposing (all)
+
+import NoDebug exposing (rule)
+import Review.Test
+import Test exposing (Test, describe, test)
+
+
+all : Test
+all =
+    describe "NoDebug"
+        [ test "should not report normal function calls" <|
+            \() ->
+                """module A exposing (..)
+a : Int
+a = foo n
+b = bar.foo n
+"""
+                    |> Review.Test.run rule
+                    |> Review.Test.expectNoErrors
+        , test "should report Debug.log use" <|
+            \() ->
+                """module A exposing (..)
+type alias Model = { count : Int }
+a = Debug.log "foo" n
+"""
+                    |> Review.Test.run rule
+                    |> Review.Test.expectErrors
+                        [ Review.Test.error
+                            { message = "Remove the use of `Debug` before shipping to production"
+                            , details = [ "Debug.log is useful when developing, but is not meant to be shipped to production." ]
+                            , under = "Debug.log"
+                            }
+                        ]
+        ]
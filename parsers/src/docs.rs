@@ -0,0 +1,360 @@
+use crate::error::ParseError;
+use crate::structs::{Associativity, Fixity, ModuleKind, TypeDefinition};
+use crate::type_scope::TypeScope;
+use crate::{constructor_exports, parse_type_signature, ElmExport, ElmExports, ElmType};
+use serde_derive::Deserialize;
+
+/// a module in the `docs.json` published for every package version on package.elm-lang.org
+#[derive(Deserialize, Debug)]
+struct ModuleDocs {
+    name: String,
+    comment: String,
+    unions: Vec<UnionDocs>,
+    aliases: Vec<AliasDocs>,
+    values: Vec<ValueDocs>,
+    binops: Vec<BinopDocs>,
+}
+
+/// a custom type, `cases` is empty if its constructors aren't exposed
+#[derive(Deserialize, Debug)]
+struct UnionDocs {
+    name: String,
+    comment: String,
+    args: Vec<String>,
+    cases: Vec<(String, Vec<String>)>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AliasDocs {
+    name: String,
+    comment: String,
+    args: Vec<String>,
+    #[serde(rename = "type")]
+    typ: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ValueDocs {
+    name: String,
+    comment: String,
+    #[serde(rename = "type")]
+    typ: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct BinopDocs {
+    name: String,
+    comment: String,
+    #[serde(rename = "type")]
+    typ: String,
+    associativity: String,
+    precedence: u8,
+}
+
+/// The exports of every module in the `docs.json` of a package.
+///
+/// Types in `docs.json` are fully qualified, `Maybe.Maybe Basics.Int`, they are resolved the
+/// same way as the types of parsed source code, so they are written `Maybe Int`. Types which
+/// can't be parsed are left out of the exports and returned in `errors`
+pub fn get_docs_exports(json: &str) -> Result<Vec<ElmExports>, serde_json::Error> {
+    let modules: Vec<ModuleDocs> = serde_json::from_str(json)?;
+    Ok(modules.iter().map(module_exports).collect())
+}
+
+fn module_exports(docs: &ModuleDocs) -> ElmExports {
+    let mut exports = ElmExports::new(&docs.name);
+    // docs.json doesn't say what kind of module a module is, it can't be derived from the
+    // exports either: packages can't expose port modules, and effect modules are documented
+    // like any other module
+    exports.kind = ModuleKind::Normal;
+    exports.documentation = documentation(&docs.comment);
+    let scope = TypeScope::new();
+    let mut errors = Vec::new();
+    // errors are reported as an annotation of the export, `name : type`, so they say which
+    // export the type belongs to
+    let mut parse = |name: &str, sig: &str| match parse_type_signature(sig) {
+        Some(typ) => Some(scope.resolve(&typ)),
+        None => {
+            let annotation = format!("{} : {}", name, sig);
+            errors.push(ParseError::new(
+                &annotation,
+                name.len() + 3,
+                "type annotation",
+            ));
+            None
+        }
+    };
+    for union in docs.unions.iter() {
+        // the constructors of opaque types aren't exposed, so they aren't in `cases`
+        let definition = if union.cases.is_empty() {
+            Some(TypeDefinition::Opaque)
+        } else {
+            union
+                .cases
+                .iter()
+                .map(|(name, args)| {
+                    let args = args
+                        .iter()
+                        .map(|arg| parse(name, arg))
+                        .collect::<Option<_>>()?;
+                    Some(ElmType::Constructor {
+                        name: name.clone(),
+                        args,
+                    })
+                })
+                .collect::<Option<Vec<ElmType>>>()
+                .map(TypeDefinition::Custom)
+        };
+        if let Some(definition) = definition {
+            let parameters: Vec<&str> = union.args.iter().map(String::as_str).collect();
+            exports.exports.push(ElmExport::Type {
+                name: union.name.clone(),
+                parameters: union.args.clone(),
+                definition: definition.clone(),
+                documentation: documentation(&union.comment),
+            });
+            // constructors return the type qualified by its module, like the other types in
+            // docs.json, so they're resolved the same way
+            let qualified = format!("{}.{}", docs.name, union.name);
+            exports.exports.extend(
+                constructor_exports(&qualified, &parameters, &definition)
+                    .into_iter()
+                    .map(|export| match export {
+                        ElmExport::Function {
+                            name,
+                            type_signature,
+                            documentation,
+                            fixity,
+                            port,
+                        } => ElmExport::Function {
+                            name,
+                            type_signature: type_signature.map(|sig| scope.resolve(&sig)),
+                            documentation,
+                            fixity,
+                            port,
+                        },
+                        export => export,
+                    }),
+            );
+        }
+    }
+    for alias in docs.aliases.iter() {
+        if let Some(aliased) = parse(&alias.name, &alias.typ) {
+            exports.exports.push(ElmExport::Type {
+                name: alias.name.clone(),
+                parameters: alias.args.clone(),
+                definition: TypeDefinition::Alias(aliased),
+                documentation: documentation(&alias.comment),
+            });
+        }
+    }
+    for value in docs.values.iter() {
+        if let Some(sig) = parse(&value.name, &value.typ) {
+            exports.exports.push(ElmExport::Function {
+                name: value.name.clone(),
+                type_signature: Some(sig),
+                documentation: documentation(&value.comment),
+                fixity: None,
+                port: false,
+            });
+        }
+    }
+    for binop in docs.binops.iter() {
+        if let Some(sig) = parse(&binop.name, &binop.typ) {
+            exports.exports.push(ElmExport::Function {
+                name: binop.name.clone(),
+                type_signature: Some(sig),
+                documentation: documentation(&binop.comment),
+                fixity: Some(Fixity {
+                    associativity: match binop.associativity.as_str() {
+                        "left" => Associativity::Left,
+                        "right" => Associativity::Right,
+                        _ => Associativity::Non,
+                    },
+                    precedence: binop.precedence,
+                }),
+                port: false,
+            });
+        }
+    }
+    exports.errors = errors;
+    exports
+}
+
+/// comments are trimmed the same way as `{-| -}` comments in source code, empty comments are
+/// left out
+fn documentation(comment: &str) -> Option<String> {
+    Some(comment.trim())
+        .filter(|comment| !comment.is_empty())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCS: &str = r#"[
+        {
+            "name": "Maybe",
+            "comment": " Optional values\n\n@docs Maybe, withDefault\n",
+            "unions": [
+                {
+                    "name": "Maybe",
+                    "comment": " Represent values that may or may not exist. ",
+                    "args": ["a"],
+                    "cases": [["Just", ["a"]], ["Nothing", []]]
+                }
+            ],
+            "aliases": [],
+            "values": [
+                {
+                    "name": "withDefault",
+                    "comment": "",
+                    "type": "a -> Maybe.Maybe a -> a"
+                },
+                {
+                    "name": "broken",
+                    "comment": "",
+                    "type": "a -> )"
+                }
+            ],
+            "binops": []
+        },
+        {
+            "name": "Url.Parser",
+            "comment": "",
+            "unions": [
+                { "name": "Parser", "comment": "", "args": ["a", "b"], "cases": [] }
+            ],
+            "aliases": [
+                {
+                    "name": "Point",
+                    "comment": "",
+                    "args": [],
+                    "type": "{ x : Basics.Float, y : Basics.Float }"
+                }
+            ],
+            "values": [],
+            "binops": [
+                {
+                    "name": "</>",
+                    "comment": " Parse a path segment ",
+                    "type": "Url.Parser.Parser a b -> Url.Parser.Parser b c -> Url.Parser.Parser a c",
+                    "associativity": "right",
+                    "precedence": 7
+                }
+            ]
+        }
+    ]"#;
+
+    fn signatures(exports: &ElmExports) -> Vec<(String, String)> {
+        exports
+            .exports
+            .iter()
+            .map(|export| match export {
+                ElmExport::Function {
+                    name,
+                    type_signature,
+                    ..
+                } => (
+                    name.clone(),
+                    type_signature
+                        .as_ref()
+                        .map(ElmType::to_string)
+                        .unwrap_or_default(),
+                ),
+                ElmExport::Type {
+                    name, definition, ..
+                } => (name.clone(), definition.to_string()),
+            })
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, sig)| (name.to_string(), sig.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_every_module() {
+        let modules = get_docs_exports(DOCS).expect("error parsing docs.json");
+        let names: Vec<&str> = modules.iter().map(|m| m.module.as_str()).collect();
+        assert_eq!(names, vec!["Maybe", "Url.Parser"]);
+        assert_eq!(
+            modules[0].documentation.as_deref(),
+            Some("Optional values\n\n@docs Maybe, withDefault")
+        );
+        assert_eq!(modules[1].documentation, None);
+    }
+
+    #[test]
+    fn resolves_qualified_types() {
+        let modules = get_docs_exports(DOCS).expect("error parsing docs.json");
+        assert_eq!(
+            signatures(&modules[0]),
+            pairs(&[
+                ("Maybe", "Just a | Nothing"),
+                ("Just", "a -> Maybe a"),
+                ("Nothing", "Maybe a"),
+                ("withDefault", "a -> Maybe a -> a"),
+            ])
+        );
+        assert_eq!(
+            signatures(&modules[1]),
+            pairs(&[
+                ("Parser", ""),
+                ("Point", "{ x : Float, y : Float }"),
+                (
+                    "</>",
+                    "Url.Parser.Parser a b -> Url.Parser.Parser b c -> Url.Parser.Parser a c"
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn unions_without_cases_are_opaque() {
+        let modules = get_docs_exports(DOCS).expect("error parsing docs.json");
+        let definitions: Vec<(&str, &TypeDefinition)> = modules
+            .iter()
+            .flat_map(|module| module.exports.iter())
+            .filter_map(|export| match export {
+                ElmExport::Type {
+                    name, definition, ..
+                } => Some((name.as_str(), definition)),
+                _ => None,
+            })
+            .filter(|(_, definition)| definition.kind() == "custom")
+            .collect();
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[0].0, "Maybe");
+        assert_eq!(definitions[1], ("Parser", &TypeDefinition::Opaque));
+    }
+
+    #[test]
+    fn reads_operator_fixity() {
+        let modules = get_docs_exports(DOCS).expect("error parsing docs.json");
+        let fixity = modules[1].exports.iter().find_map(|export| match export {
+            ElmExport::Function { fixity, .. } => *fixity,
+            _ => None,
+        });
+        assert_eq!(
+            fixity,
+            Some(Fixity {
+                associativity: Associativity::Right,
+                precedence: 7
+            })
+        );
+    }
+
+    #[test]
+    fn reports_types_which_cant_be_parsed() {
+        let modules = get_docs_exports(DOCS).expect("error parsing docs.json");
+        assert_eq!(modules[0].errors.len(), 1);
+        assert_eq!(modules[0].errors[0].excerpt, "broken : a -> )");
+        assert_eq!(modules[0].errors[0].column, 10);
+        assert!(get_docs_exports("{").is_err());
+    }
+}
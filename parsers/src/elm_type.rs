@@ -0,0 +1,534 @@
+use hashbrown::HashMap;
+use std::fmt;
+
+/// prefixes of type variables which elm restricts to a class of types
+pub const CONSTRAINED_VARIABLES: [&str; 4] = ["number", "comparable", "appendable", "compappend"];
+
+/// A parsed elm type expression, such as `(a -> b) -> List a -> List b`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ElmType {
+    /// a (possibly qualified) type constructor applied to zero or more arguments,
+    /// e.g. `Int`, `List a` or `Dict.Dict String Int`
+    Constructor { name: String, args: Vec<ElmType> },
+    /// a type variable, e.g. `a` or `comparable`
+    Variable(String),
+    /// a function, with curried arguments flattened,
+    /// `a -> b -> c` has args `[a, b]` and returns `c`
+    Function {
+        args: Vec<ElmType>,
+        ret: Box<ElmType>,
+    },
+    /// a tuple with two or more elements, e.g. `( a, b )`
+    Tuple(Vec<ElmType>),
+    /// a record, e.g. `{ x : Float, y : Float }`
+    Record(Vec<(String, ElmType)>),
+    /// an extensible record, e.g. `{ a | name : String }`
+    ExtensibleRecord {
+        base: String,
+        fields: Vec<(String, ElmType)>,
+    },
+    /// the unit type `()`
+    Unit,
+}
+
+impl ElmType {
+    /// builds a function type, flattening a function in return position into the argument list
+    pub fn function(args: Vec<ElmType>, ret: ElmType) -> ElmType {
+        let mut args = args;
+        match ret {
+            ElmType::Function {
+                args: ret_args,
+                ret,
+            } => {
+                args.extend(ret_args);
+                ElmType::Function { args, ret }
+            }
+            ret => {
+                if args.is_empty() {
+                    ret
+                } else {
+                    ElmType::Function {
+                        args,
+                        ret: Box::new(ret),
+                    }
+                }
+            }
+        }
+    }
+
+    /// renames type variables in order of first appearance, so that types which only differ in
+    /// their variable names (`a -> List a` and `x -> List x`) are equal and display identically
+    ///
+    /// constrained variables keep their constraint, `comparable` and `comparableKey` become
+    /// `comparable` and `comparable1`. record fields are sorted by name, so
+    /// `{ y : Float, x : Float }` becomes `{ x : Float, y : Float }`
+    pub fn normalize(&self) -> ElmType {
+        self.rename_variables(&mut VariableNames::default())
+    }
+
+    fn rename_variables(&self, names: &mut VariableNames) -> ElmType {
+        let rename_fields = |fields: &[(String, ElmType)], names: &mut VariableNames| {
+            // variables are named in the order they appear in the sorted fields
+            let mut fields: Vec<&(String, ElmType)> = fields.iter().collect();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            fields
+                .into_iter()
+                .map(|(name, typ)| (name.clone(), typ.rename_variables(names)))
+                .collect()
+        };
+        match self {
+            ElmType::Constructor { name, args } => ElmType::Constructor {
+                name: name.clone(),
+                args: args.iter().map(|arg| arg.rename_variables(names)).collect(),
+            },
+            ElmType::Variable(name) => ElmType::Variable(names.rename(name)),
+            ElmType::Function { args, ret } => {
+                let args = args.iter().map(|arg| arg.rename_variables(names)).collect();
+                ElmType::Function {
+                    args,
+                    ret: Box::new(ret.rename_variables(names)),
+                }
+            }
+            ElmType::Tuple(types) => {
+                ElmType::Tuple(types.iter().map(|t| t.rename_variables(names)).collect())
+            }
+            ElmType::Record(fields) => ElmType::Record(rename_fields(fields, names)),
+            ElmType::ExtensibleRecord { base, fields } => {
+                let base = names.rename(base);
+                ElmType::ExtensibleRecord {
+                    base,
+                    fields: rename_fields(fields, names),
+                }
+            }
+            ElmType::Unit => ElmType::Unit,
+        }
+    }
+
+    /// replaces type variables with the types in vars, variables not in vars are left alone
+    fn substitute(&self, vars: &HashMap<&str, &ElmType>) -> ElmType {
+        let substitute_fields = |fields: &[(String, ElmType)]| -> Vec<(String, ElmType)> {
+            fields
+                .iter()
+                .map(|(name, typ)| (name.clone(), typ.substitute(vars)))
+                .collect()
+        };
+        match self {
+            ElmType::Constructor { name, args } => ElmType::Constructor {
+                name: name.clone(),
+                args: args.iter().map(|arg| arg.substitute(vars)).collect(),
+            },
+            ElmType::Variable(name) => match vars.get(name.as_str()) {
+                Some(typ) => (*typ).clone(),
+                None => self.clone(),
+            },
+            ElmType::Function { args, ret } => ElmType::function(
+                args.iter().map(|arg| arg.substitute(vars)).collect(),
+                ret.substitute(vars),
+            ),
+            ElmType::Tuple(types) => {
+                ElmType::Tuple(types.iter().map(|t| t.substitute(vars)).collect())
+            }
+            ElmType::Record(fields) => ElmType::Record(substitute_fields(fields)),
+            // `{ a | name : String }` with a record for `a` is that record with the extra fields
+            ElmType::ExtensibleRecord { base, fields } => {
+                let mut fields = substitute_fields(fields);
+                match vars.get(base.as_str()) {
+                    Some(ElmType::Record(base_fields)) => {
+                        let mut base_fields = base_fields.clone();
+                        base_fields.append(&mut fields);
+                        ElmType::Record(base_fields)
+                    }
+                    Some(ElmType::ExtensibleRecord {
+                        base,
+                        fields: base_fields,
+                    }) => {
+                        let mut base_fields = base_fields.clone();
+                        base_fields.append(&mut fields);
+                        ElmType::ExtensibleRecord {
+                            base: base.clone(),
+                            fields: base_fields,
+                        }
+                    }
+                    Some(ElmType::Variable(base)) => ElmType::ExtensibleRecord {
+                        base: base.clone(),
+                        fields,
+                    },
+                    _ => ElmType::ExtensibleRecord {
+                        base: base.clone(),
+                        fields,
+                    },
+                }
+            }
+            ElmType::Unit => ElmType::Unit,
+        }
+    }
+
+    /// true if this type must be wrapped in parentheses when used as a type constructor argument
+    fn is_compound(&self) -> bool {
+        match self {
+            ElmType::Constructor { args, .. } => !args.is_empty(),
+            ElmType::Function { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Type aliases by name, used to replace the aliases in a type with the types they stand for.
+///
+/// `type alias Decoder a = Value -> Result Error a` expands `Decoder Int` to
+/// `Value -> Result Error Int`
+#[derive(Debug, Default, Clone)]
+pub struct TypeAliases {
+    aliases: HashMap<String, (Vec<String>, ElmType)>,
+}
+
+impl TypeAliases {
+    pub fn new() -> Self {
+        TypeAliases::default()
+    }
+
+    /// adds `type alias name parameters = aliased`, replacing any alias with the same name
+    pub fn insert(&mut self, name: &str, parameters: &[String], aliased: &ElmType) {
+        self.aliases
+            .insert(name.to_string(), (parameters.to_vec(), aliased.clone()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// replaces every alias in typ with the type it stands for, including aliases used in the
+    /// definitions of other aliases
+    ///
+    /// aliases applied to the wrong number of arguments are left alone
+    pub fn expand(&self, typ: &ElmType) -> ElmType {
+        self.expand_within(typ, &mut Vec::new())
+    }
+
+    /// expanding holds the aliases currently being expanded, which are not expanded again in
+    /// case an alias refers to itself
+    fn expand_within(&self, typ: &ElmType, expanding: &mut Vec<String>) -> ElmType {
+        let expand_fields = |fields: &[(String, ElmType)], expanding: &mut Vec<String>| {
+            fields
+                .iter()
+                .map(|(name, typ)| (name.clone(), self.expand_within(typ, expanding)))
+                .collect()
+        };
+        match typ {
+            ElmType::Constructor { name, args } => {
+                let args: Vec<ElmType> = args
+                    .iter()
+                    .map(|arg| self.expand_within(arg, expanding))
+                    .collect();
+                match self.aliases.get(name) {
+                    Some((parameters, aliased))
+                        if parameters.len() == args.len() && !expanding.contains(name) =>
+                    {
+                        let vars: HashMap<&str, &ElmType> =
+                            parameters.iter().map(String::as_str).zip(&args).collect();
+                        expanding.push(name.clone());
+                        let expanded = self.expand_within(&aliased.substitute(&vars), expanding);
+                        expanding.pop();
+                        expanded
+                    }
+                    _ => ElmType::Constructor {
+                        name: name.clone(),
+                        args,
+                    },
+                }
+            }
+            ElmType::Variable(_) | ElmType::Unit => typ.clone(),
+            ElmType::Function { args, ret } => ElmType::function(
+                args.iter()
+                    .map(|arg| self.expand_within(arg, expanding))
+                    .collect(),
+                self.expand_within(ret, expanding),
+            ),
+            ElmType::Tuple(types) => ElmType::Tuple(
+                types
+                    .iter()
+                    .map(|t| self.expand_within(t, expanding))
+                    .collect(),
+            ),
+            ElmType::Record(fields) => ElmType::Record(expand_fields(fields, expanding)),
+            ElmType::ExtensibleRecord { base, fields } => ElmType::ExtensibleRecord {
+                base: base.clone(),
+                fields: expand_fields(fields, expanding),
+            },
+        }
+    }
+}
+
+/// assigns canonical names to type variables as they are encountered
+#[derive(Default)]
+struct VariableNames {
+    names: HashMap<String, String>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl VariableNames {
+    fn rename(&mut self, name: &str) -> String {
+        if let Some(new_name) = self.names.get(name) {
+            return new_name.clone();
+        }
+        let class = CONSTRAINED_VARIABLES
+            .iter()
+            .find(|class| name.starts_with(*class))
+            .cloned()
+            .unwrap_or("");
+        let count = self.counts.entry(class).or_insert(0);
+        let new_name = match (class, *count) {
+            ("", n) => {
+                let letter = (b'a' + (n % 26) as u8) as char;
+                if n < 26 {
+                    letter.to_string()
+                } else {
+                    format!("{}{}", letter, n / 26)
+                }
+            }
+            (class, 0) => class.to_string(),
+            (class, n) => format!("{}{}", class, n),
+        };
+        *count += 1;
+        self.names.insert(name.to_string(), new_name.clone());
+        new_name
+    }
+}
+
+/// normalizes the start of a type signature, which may not parse yet, so it is a prefix of the
+/// normalized signatures it is the start of. `x  ->  List` becomes `a -> List`
+///
+/// whitespace is collapsed and type variables are renamed in order of appearance, the way
+/// `ElmType::normalize` renames them. A name at the very end of prefix may be incomplete and is
+/// left as it is, as are record field names
+pub fn normalize_prefix(prefix: &str) -> String {
+    let mut names = VariableNames::default();
+    let mut normalized = String::new();
+    let mut rest = prefix.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            normalized.push(' ');
+            rest = rest.trim_start();
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            let (name, after) = rest.split_at(end);
+            let is_variable = c.is_lowercase()
+                && !name.contains('.')
+                && !after.is_empty()
+                && !after.trim_start().starts_with(':');
+            if is_variable {
+                normalized.push_str(&names.rename(name));
+            } else {
+                normalized.push_str(name);
+            }
+            rest = after;
+        } else {
+            normalized.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    normalized
+}
+
+fn fmt_fields(f: &mut fmt::Formatter, fields: &[(String, ElmType)]) -> fmt::Result {
+    for (i, (name, typ)) in fields.iter().enumerate() {
+        if i != 0 {
+            write!(f, ",")?;
+        }
+        write!(f, " {} : {}", name, typ)?;
+    }
+    write!(f, " }}")
+}
+
+impl fmt::Display for ElmType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElmType::Constructor { name, args } => {
+                write!(f, "{}", name)?;
+                for arg in args {
+                    if arg.is_compound() {
+                        write!(f, " ({})", arg)?;
+                    } else {
+                        write!(f, " {}", arg)?;
+                    }
+                }
+                Ok(())
+            }
+            ElmType::Variable(name) => write!(f, "{}", name),
+            ElmType::Function { args, ret } => {
+                for arg in args {
+                    if let ElmType::Function { .. } = arg {
+                        write!(f, "({}) -> ", arg)?;
+                    } else {
+                        write!(f, "{} -> ", arg)?;
+                    }
+                }
+                write!(f, "{}", ret)
+            }
+            ElmType::Tuple(types) => {
+                if types.is_empty() {
+                    return write!(f, "()");
+                }
+                write!(f, "(")?;
+                for (i, typ) in types.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {}", typ)?;
+                }
+                write!(f, " )")
+            }
+            ElmType::Record(fields) => {
+                if fields.is_empty() {
+                    return write!(f, "{{}}");
+                }
+                write!(f, "{{")?;
+                fmt_fields(f, fields)
+            }
+            ElmType::ExtensibleRecord { base, fields } => {
+                write!(f, "{{ {} |", base)?;
+                fmt_fields(f, fields)
+            }
+            ElmType::Unit => write!(f, "()"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_prefix, TypeAliases};
+    use crate::parse_type_signature;
+
+    fn normalize(sig: &str) -> String {
+        parse_type_signature(sig)
+            .expect("error parsing type signature")
+            .normalize()
+            .to_string()
+    }
+
+    #[test]
+    fn normalize_renames_in_order_of_appearance() {
+        assert_eq!(normalize("x -> List x"), "a -> List a");
+        assert_eq!(normalize("b -> a -> b"), "a -> b -> a");
+        assert_eq!(
+            normalize("(item -> acc -> acc) -> acc -> List item -> acc"),
+            "(a -> b -> b) -> b -> List a -> b"
+        );
+    }
+
+    #[test]
+    fn normalize_keeps_constraints() {
+        assert_eq!(
+            normalize("comparableKey -> v -> Dict comparableKey v"),
+            "comparable -> a -> Dict comparable a"
+        );
+        assert_eq!(
+            normalize("comparable2 -> comparable1 -> number -> ( comparable1, comparable2 )"),
+            "comparable -> comparable1 -> number -> ( comparable1, comparable )"
+        );
+    }
+
+    #[test]
+    fn normalize_canonicalizes_layout() {
+        assert_eq!(normalize("  Maybe   (a)->(List  b )"), "Maybe a -> List b");
+        assert_eq!(normalize("{ r | x : a }"), "{ a | x : b }");
+    }
+
+    #[test]
+    fn normalize_sorts_record_fields() {
+        assert_eq!(
+            normalize("{ y : b, x : a } -> Float"),
+            "{ x : a, y : b } -> Float"
+        );
+        assert_eq!(
+            normalize("{ r | name : String, age : Int }"),
+            normalize("{ s | age : Int, name : String }")
+        );
+    }
+
+    #[test]
+    fn normalize_equates_alpha_equivalent_types() {
+        assert_eq!(normalize("a -> a"), normalize("b -> b"));
+        assert_ne!(normalize("a -> b"), normalize("a -> a"));
+    }
+
+    #[test]
+    fn normalize_prefix_renames_complete_variables() {
+        assert_eq!(normalize_prefix("  x  ->  List "), "a -> List ");
+        assert_eq!(normalize_prefix("x -> y -> x"), "a -> b -> x");
+        assert_eq!(
+            normalize_prefix("comparableKey -> v -> Dict comparableKey v "),
+            "comparable -> a -> Dict comparable a "
+        );
+        assert_eq!(
+            normalize_prefix("{ r | name : String } -> Ma"),
+            "{ a | name : String } -> Ma"
+        );
+    }
+
+    fn aliases(aliases: &[(&str, &[&str], &str)]) -> TypeAliases {
+        let mut res = TypeAliases::new();
+        for (name, parameters, aliased) in aliases {
+            let parameters: Vec<String> = parameters.iter().map(|p| p.to_string()).collect();
+            let aliased = parse_type_signature(aliased).expect("error parsing aliased type");
+            res.insert(name, &parameters, &aliased);
+        }
+        res
+    }
+
+    fn expand(aliases: &TypeAliases, sig: &str) -> String {
+        aliases
+            .expand(&parse_type_signature(sig).expect("error parsing type signature"))
+            .to_string()
+    }
+
+    #[test]
+    fn expand_replaces_aliases() {
+        let aliases = aliases(&[
+            ("Point", &[], "{ x : Float, y : Float }"),
+            ("Decoder", &["a"], "Value -> Result Error a"),
+            ("Pair", &["a", "b"], "( a, b )"),
+        ]);
+        assert_eq!(
+            expand(&aliases, "Point -> Float"),
+            "{ x : Float, y : Float } -> Float"
+        );
+        assert_eq!(
+            expand(&aliases, "Decoder (Pair Int a)"),
+            "Value -> Result Error ( Int, a )"
+        );
+        // aliases to functions in return position are flattened
+        assert_eq!(
+            expand(&aliases, "String -> Decoder Int"),
+            "String -> Value -> Result Error Int"
+        );
+        assert_eq!(expand(&aliases, "Pair Int"), "Pair Int");
+        assert_eq!(expand(&aliases, "List Int"), "List Int");
+    }
+
+    #[test]
+    fn expand_nested_aliases() {
+        let aliases = aliases(&[
+            ("Model", &[], "{ position : Point, name : Name }"),
+            ("Point", &[], "( Float, Float )"),
+            ("Name", &[], "String"),
+            ("Named", &["a"], "{ a | name : Name }"),
+        ]);
+        assert_eq!(
+            expand(&aliases, "Model -> Name"),
+            "{ position : ( Float, Float ), name : String } -> String"
+        );
+        assert_eq!(
+            expand(&aliases, "Named { age : Int } -> Named b"),
+            "{ age : Int, name : String } -> { b | name : String }"
+        );
+    }
+
+    #[test]
+    fn expand_stops_at_recursive_aliases() {
+        let aliases = aliases(&[("Loop", &[], "List Loop")]);
+        assert_eq!(expand(&aliases, "Loop"), "List Loop");
+    }
+}
@@ -0,0 +1,67 @@
+use std::error::Error;
+use std::fmt;
+
+/// An error parsing elm code, along with where it occurred
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// the byte offset of the error in the code
+    pub offset: usize,
+    /// the line the error is on, starting at 1
+    pub line: usize,
+    /// the column the error is in, in characters starting at 1
+    pub column: usize,
+    /// what was being parsed, e.g. `type annotation`
+    pub construct: &'static str,
+    /// the line of code the error is on
+    pub excerpt: String,
+}
+
+impl ParseError {
+    /// an error parsing construct at offset in code, offset must be on a character boundary
+    pub(crate) fn new(code: &str, offset: usize, construct: &'static str) -> Self {
+        let before = &code[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = code[offset..].find('\n').map_or(code.len(), |i| offset + i);
+        ParseError {
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            construct,
+            excerpt: code[line_start..line_end].trim_end().to_string(),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: error parsing {}\n    {}\n    {:>width$}",
+            self.line,
+            self.column,
+            self.construct,
+            self.excerpt,
+            "^",
+            width = self.column
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseError;
+
+    #[test]
+    fn locates_errors() {
+        let code = "module A exposing (..)\n\nx : Int ->\nx = 1\n";
+        let err = ParseError::new(code, 28, "type annotation");
+        assert_eq!((err.line, err.column), (3, 5));
+        assert_eq!(err.excerpt, "x : Int ->");
+        assert_eq!(
+            err.to_string(),
+            "3:5: error parsing type annotation\n    x : Int ->\n        ^"
+        );
+    }
+}
@@ -2,18 +2,28 @@ pub fn is_space_or_newline(c: char) -> bool {
     c.is_whitespace() || c == '\n'
 }
 
+pub fn is_space(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
 pub fn is_alphanumeric(c: char) -> bool {
     c.is_alphanumeric() || c == '_'
 }
 
+pub fn is_module_name(c: char) -> bool {
+    is_alphanumeric(c) || c == '.'
+}
+
 pub fn is_operator(c: char) -> bool {
-    ['+', '-', '/', '*', '^', '=', '>', '<', '&', '|']
-        .iter()
-        .any(|&x| x == c)
+    [
+        '+', '-', '/', '*', '^', '=', '>', '<', '&', '|', '.', ':', '?', '!', '%', '$', '~', '@',
+        '#',
+    ]
+    .contains(&c)
 }
 
 pub fn is_allowed_for_types_and_functions(c: char) -> bool {
-    is_alphanumeric(c) || ['.', ','].iter().any(|&x| x == c)
+    is_alphanumeric(c) || ['.', ','].contains(&c)
 }
 
 pub fn is_space_or_newline_or_comma(c: char) -> bool {
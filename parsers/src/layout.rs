@@ -0,0 +1,165 @@
+use std::ops::Range;
+
+#[derive(Clone, Copy)]
+enum Lexer {
+    Code,
+    LineComment,
+    /// a `{- -}` comment, which may be nested
+    BlockComment(usize),
+    String,
+    MultilineString,
+    Char,
+    /// a `[glsl| |]` shader
+    Glsl,
+}
+
+/// Splits elm code into its top level declarations. A declaration starts on a line which
+/// isn't indented and runs until the next line which isn't indented, lines in comments, strings
+/// and shaders never start a declaration. Code before the first unindented line is skipped
+pub(crate) fn top_level_declarations(code: &str) -> Vec<Range<usize>> {
+    // every delimiter is ascii, so bytes which are part of a multibyte character never match
+    let bytes = code.as_bytes();
+    let mut starts = Vec::new();
+    let mut lexer = Lexer::Code;
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let (next, len) = match lexer {
+            Lexer::Code => {
+                if (i == 0 || bytes[i - 1] == b'\n') && !bytes[i].is_ascii_whitespace() {
+                    starts.push(i);
+                }
+                if rest.starts_with(b"{-") {
+                    (Lexer::BlockComment(1), 2)
+                } else if rest.starts_with(b"--") {
+                    (Lexer::LineComment, 2)
+                } else if rest.starts_with(b"\"\"\"") {
+                    (Lexer::MultilineString, 3)
+                } else if rest.starts_with(b"\"") {
+                    (Lexer::String, 1)
+                } else if rest.starts_with(b"[glsl|") {
+                    (Lexer::Glsl, 6)
+                } else if rest.starts_with(b"'") && (i == 0 || !is_identifier(bytes[i - 1])) {
+                    // a quote following a name is part of the name in elm 0.18, `model'`
+                    (Lexer::Char, 1)
+                } else {
+                    (Lexer::Code, 1)
+                }
+            }
+            Lexer::LineComment if rest[0] == b'\n' => (Lexer::Code, 1),
+            Lexer::BlockComment(depth) if rest.starts_with(b"{-") => {
+                (Lexer::BlockComment(depth + 1), 2)
+            }
+            Lexer::BlockComment(1) if rest.starts_with(b"-}") => (Lexer::Code, 2),
+            Lexer::BlockComment(depth) if rest.starts_with(b"-}") => {
+                (Lexer::BlockComment(depth - 1), 2)
+            }
+            Lexer::String | Lexer::MultilineString | Lexer::Char if rest[0] == b'\\' => (lexer, 2),
+            // unterminated strings and characters end at the end of the line
+            Lexer::String | Lexer::Char if rest[0] == b'\n' => (Lexer::Code, 1),
+            Lexer::String if rest[0] == b'"' => (Lexer::Code, 1),
+            Lexer::MultilineString if rest.starts_with(b"\"\"\"") => (Lexer::Code, 3),
+            Lexer::Char if rest[0] == b'\'' => (Lexer::Code, 1),
+            Lexer::Glsl if rest.starts_with(b"|]") => (Lexer::Code, 2),
+            _ => (lexer, 1),
+        };
+        lexer = next;
+        i += len;
+    }
+    let ends = starts.iter().skip(1).cloned().chain(Some(code.len()));
+    starts.iter().zip(ends).map(|(&s, e)| s..e).collect()
+}
+
+fn is_identifier(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// the name a value declaration starts with, `map` in `map f xs =` and `(|>)` in `(|>) x f =`
+fn value_name(declaration: &str) -> Option<&str> {
+    let end = if declaration.starts_with('(') {
+        declaration.find(')')? + 1
+    } else if declaration.starts_with(|c: char| c.is_lowercase()) {
+        declaration
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(declaration.len())
+    } else {
+        return None;
+    };
+    Some(&declaration[..end])
+}
+
+/// the name of the value a type annotation, `map : (a -> b) -> List a -> List b`, is for
+pub(crate) fn annotated_name(declaration: &str) -> Option<&str> {
+    let name = value_name(declaration)?;
+    let rest = declaration[name.len()..].trim_start();
+    if rest.starts_with(':') && !rest.starts_with("::") {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+/// true if declaration is the definition of the value called name, rather than its annotation
+pub(crate) fn defines(declaration: &str, name: &str) -> bool {
+    value_name(declaration) == Some(name) && annotated_name(declaration).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declarations(code: &str) -> Vec<&str> {
+        top_level_declarations(code)
+            .into_iter()
+            .map(|r| &code[r])
+            .collect()
+    }
+
+    #[test]
+    fn splits_at_unindented_lines() {
+        assert_eq!(
+            declarations("module A exposing (..)\n\nx : Int\nx =\n    1\n\ny = 2"),
+            vec![
+                "module A exposing (..)\n\n",
+                "x : Int\n",
+                "x =\n    1\n\n",
+                "y = 2"
+            ]
+        );
+        assert_eq!(declarations("  indented\nx = 1\n"), vec!["x = 1\n"]);
+    }
+
+    #[test]
+    fn skips_lines_in_comments_strings_and_shaders() {
+        let code = "x =\n    \"\"\"\ny : Int\n\"\"\"\n{- {- nested -}\nz = 3\n-}\n\
+                    s = \"{-\"\nc = '\"'\nshader =\n    [glsl|\nvoid main () {}\n|]\n";
+        assert_eq!(
+            declarations(code),
+            vec![
+                "x =\n    \"\"\"\ny : Int\n\"\"\"\n",
+                "{- {- nested -}\nz = 3\n-}\n",
+                "s = \"{-\"\n",
+                "c = '\"'\n",
+                "shader =\n    [glsl|\nvoid main () {}\n|]\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn pairs_annotations_with_definitions() {
+        assert_eq!(
+            annotated_name("map : (a -> b) -> List a -> List b"),
+            Some("map")
+        );
+        assert_eq!(
+            annotated_name("(|>)\n    : a -> (a -> b) -> b"),
+            Some("(|>)")
+        );
+        assert_eq!(annotated_name("map f xs ="), None);
+        assert_eq!(annotated_name("Maybe : a"), None);
+        assert!(defines("map f xs =\n    foldr", "map"));
+        assert!(defines("(|>) x f =", "(|>)"));
+        assert!(!defines("mapper f =", "map"));
+        assert!(!defines("map : Int", "map"));
+    }
+}
@@ -1,76 +1,196 @@
 #[macro_use]
 extern crate nom;
 
+pub(crate) mod docs;
+pub(crate) mod elm_type;
+pub(crate) mod error;
 pub(crate) mod helpers;
+pub(crate) mod layout;
 pub(crate) mod parser;
 pub(crate) mod structs;
+pub(crate) mod type_scope;
 
-use crate::parser::elm;
-use crate::structs::{ElmCode, ElmModule, Function, Type, TypeOrFunction};
-use hashbrown::HashSet;
+pub use crate::docs::get_docs_exports;
+pub use crate::elm_type::{normalize_prefix, ElmType, TypeAliases, CONSTRAINED_VARIABLES};
+pub use crate::error::ParseError;
+use crate::parser::{elm, type_signature};
+pub use crate::structs::{Associativity, Fixity, ModuleKind, TypeDefinition};
+use crate::structs::{
+    ElmCode, ElmModule, Function, Infix, ModuleDeclaration, Type, TypeOrFunction,
+};
+pub use crate::type_scope::TypeScope;
+use hashbrown::{HashMap, HashSet};
+use nom::types::CompleteStr;
 
 #[derive(Debug, Clone)]
 pub enum ElmExport {
     Function {
         name: String,
-        type_signature: Option<Vec<String>>,
+        type_signature: Option<ElmType>,
+        /// the contents of the `{-| -}` comment preceding the function
+        documentation: Option<String>,
+        /// the associativity and precedence of an operator, `None` for other functions
+        fixity: Option<Fixity>,
+        /// true for ports, `port send : String -> Cmd msg`
+        port: bool,
     },
     Type {
         name: String,
-        definition: String,
+        /// the names of the type variables the type takes, e.g. `a` in `Maybe a`
+        parameters: Vec<String>,
+        definition: TypeDefinition,
+        /// the contents of the `{-| -}` comment preceding the type
+        documentation: Option<String>,
     },
 }
 
 #[derive(Debug, Clone)]
 pub struct ElmExports {
+    /// the name of the module, e.g. `Json.Decode`
+    pub module: String,
+    /// whether the module is a port module, an effect module or neither
+    pub kind: ModuleKind,
     pub exports: Vec<ElmExport>,
+    /// the contents of the `{-| -}` comment following the module declaration
+    pub documentation: Option<String>,
+    /// the types the module can refer to, used to resolve the types in its signatures
+    pub scope: TypeScope,
+    /// top level declarations which couldn't be parsed, and were skipped
+    pub errors: Vec<ParseError>,
 }
 
 impl ElmExports {
-    fn new() -> ElmExports {
-        ElmExports { exports: vec![] }
+    fn new(module: &str) -> ElmExports {
+        ElmExports {
+            module: String::from(module),
+            kind: ModuleKind::Normal,
+            exports: vec![],
+            documentation: None,
+            scope: TypeScope::new(),
+            errors: vec![],
+        }
     }
 }
 
-pub fn get_elm_exports(code: &str) -> Result<ElmExports, ()> {
-    let (_, (module, elm_code)) = match elm(code) {
-        Ok(v) => v,
-        Err(_) => return Err(()),
-    };
+/// the functions and types exported by the elm module in code, returns an error if the module
+/// declaration can't be parsed. Other declarations which can't be parsed are skipped, and
+/// returned in `errors`
+pub fn get_elm_exports(code: &str) -> Result<ElmExports, ParseError> {
+    let (
+        ModuleDeclaration {
+            kind,
+            name,
+            exposing,
+        },
+        elm_code,
+    ) = elm(code)?;
 
-    if let ElmModule::List(l) = module {
-        Ok(exports_from_module_list(l.as_ref(), elm_code.as_ref()))
+    let mut exports = if let ElmModule::List(l) = exposing {
+        exports_from_module_list(name, l.as_ref(), elm_code.as_ref())
     } else {
-        Ok(exports_from_module_all(elm_code.as_ref()))
+        exports_from_module_all(name, elm_code.as_ref())
+    };
+    exports.kind = kind;
+    exports.scope = TypeScope::from_code(name, elm_code.as_ref());
+    exports.errors = elm_code
+        .into_iter()
+        .filter_map(|code_bit| match code_bit {
+            ElmCode::Unparsed(e) => Some(e),
+            _ => None,
+        })
+        .collect();
+    Ok(exports)
+}
+
+/// parse a type signature, such as `(a -> b) -> List a -> List b`
+pub fn parse_type_signature(sig: &str) -> Option<ElmType> {
+    match type_signature(CompleteStr(sig)) {
+        Ok((_, typ)) => Some(typ),
+        Err(_) => None,
     }
 }
 
-fn exports_from_module_list(l: &[TypeOrFunction], elm_code: &[ElmCode]) -> ElmExports {
-    let mut exports = ElmExports::new();
+/// the constructors of a custom type as functions returning the type,
+/// `type Maybe a = Just a | Nothing` has constructors `Just : a -> Maybe a` and `Nothing : Maybe a`
+fn constructor_exports(
+    type_name: &str,
+    parameters: &[&str],
+    definition: &TypeDefinition,
+) -> Vec<ElmExport> {
+    let constructors = match definition {
+        TypeDefinition::Custom(constructors) => constructors,
+        TypeDefinition::Alias(_) | TypeDefinition::Opaque => return vec![],
+    };
+    let typ = ElmType::Constructor {
+        name: String::from(type_name),
+        args: parameters
+            .iter()
+            .map(|p| ElmType::Variable(String::from(*p)))
+            .collect(),
+    };
+    constructors
+        .iter()
+        .filter_map(|constructor| match constructor {
+            ElmType::Constructor { name, args } => Some(ElmExport::Function {
+                name: name.clone(),
+                type_signature: Some(ElmType::function(args.clone(), typ.clone())),
+                documentation: None,
+                fixity: None,
+                port: false,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn exports_from_module_list(
+    module: &str,
+    l: &[TypeOrFunction],
+    elm_code: &[ElmCode],
+) -> ElmExports {
+    let mut exports = ElmExports::new(module);
+    let fixities = fixities(elm_code);
     // get a set containing all types & functions that will be exported and we care about
     let to_export: HashSet<&str> = l
         .iter()
         .map(|export| match export {
-            TypeOrFunction::Type(Type { ref name, .. }) => *name,
-            TypeOrFunction::Function(Function { ref name, .. }) => *name,
+            TypeOrFunction::Type(Type { name, .. }) => *name,
+            TypeOrFunction::TypeWithConstructors(Type { name, .. }) => *name,
+            TypeOrFunction::Function(Function { name, .. }) => *name,
+        })
+        .collect();
+    // types whose constructors are exported as well, `Maybe(..)`
+    let with_constructors: HashSet<&str> = l
+        .iter()
+        .filter_map(|export| match export {
+            TypeOrFunction::TypeWithConstructors(Type { name, .. }) => Some(*name),
+            _ => None,
         })
         .collect();
     // collect functions and types that are defined in the module exports
     for exp in l.iter() {
         match exp {
             TypeOrFunction::Type(Type {
-                ref name,
+                name,
+                parameters,
                 definition: Some(def),
+                documentation,
             }) => exports.exports.push(ElmExport::Type {
                 name: String::from(*name),
-                definition: String::from(*def),
+                parameters: parameters.iter().map(|p| String::from(*p)).collect(),
+                definition: def.clone(),
+                documentation: documentation.map(String::from),
             }),
             TypeOrFunction::Function(Function {
-                ref name,
+                name,
                 type_signature: Some(sig),
+                documentation,
             }) => exports.exports.push(ElmExport::Function {
                 name: String::from(*name),
                 type_signature: Some(sig.clone()),
+                documentation: documentation.map(String::from),
+                fixity: None,
+                port: false,
             }),
             // ignore if there is not an inline definition
             _ => {}
@@ -79,61 +199,322 @@ fn exports_from_module_list(l: &[TypeOrFunction], elm_code: &[ElmCode]) -> ElmEx
     // collect functions and types from code
     for code_bit in elm_code.iter() {
         match code_bit {
+            ElmCode::ModuleDocumentation(doc) => exports.documentation = Some(String::from(*doc)),
             ElmCode::Type(Type {
-                ref name,
-                ref definition,
-            }) => {
-                if to_export.contains(*name) {
-                    exports.exports.push(ElmExport::Type {
-                        name: String::from(*name),
-                        definition: definition
-                            .map(|def| String::from(def))
-                            .unwrap_or_else(|| String::new()),
-                    })
+                name,
+                parameters,
+                definition: Some(definition),
+                documentation,
+            }) if to_export.contains(*name) => {
+                exports.exports.push(ElmExport::Type {
+                    name: String::from(*name),
+                    parameters: parameters.iter().map(|p| String::from(*p)).collect(),
+                    definition: definition.clone(),
+                    documentation: documentation.map(String::from),
+                });
+                if with_constructors.contains(*name) {
+                    exports
+                        .exports
+                        .extend(constructor_exports(name, parameters, definition));
                 }
             }
             ElmCode::Function(Function {
-                ref name,
-                ref type_signature,
-            }) => {
-                if to_export.contains(*name) {
-                    exports.exports.push(ElmExport::Function {
-                        name: String::from(*name),
-                        type_signature: type_signature.clone(),
-                    })
-                }
+                name,
+                type_signature,
+                documentation,
+            }) if to_export.contains(*name) => exports.exports.push(ElmExport::Function {
+                name: String::from(*name),
+                type_signature: type_signature.clone(),
+                documentation: documentation.map(String::from),
+                fixity: fixities.get(name).cloned(),
+                port: false,
+            }),
+            ElmCode::Port(port) if to_export.contains(port.name) => {
+                exports.exports.push(port_export(port))
             }
             // do nothing
             _ => {}
         }
     }
     exports
+        .exports
+        .extend(operator_exports(elm_code, |op| to_export.contains(op)));
+    exports
 }
 
-fn exports_from_module_all(elm_code: &[ElmCode]) -> ElmExports {
-    let mut exports = ElmExports::new();
+fn exports_from_module_all(module: &str, elm_code: &[ElmCode]) -> ElmExports {
+    let mut exports = ElmExports::new(module);
+    let fixities = fixities(elm_code);
     // collect functions and types from code
     for code_bit in elm_code.iter() {
         match code_bit {
+            ElmCode::ModuleDocumentation(doc) => exports.documentation = Some(String::from(*doc)),
             ElmCode::Type(Type {
-                ref name,
-                ref definition,
-            }) => exports.exports.push(ElmExport::Type {
-                name: String::from(*name),
-                definition: definition
-                    .map(|def| String::from(def))
-                    .unwrap_or_else(|| String::new()),
-            }),
+                name,
+                parameters,
+                definition: Some(definition),
+                documentation,
+            }) => {
+                exports.exports.push(ElmExport::Type {
+                    name: String::from(*name),
+                    parameters: parameters.iter().map(|p| String::from(*p)).collect(),
+                    definition: definition.clone(),
+                    documentation: documentation.map(String::from),
+                });
+                exports
+                    .exports
+                    .extend(constructor_exports(name, parameters, definition));
+            }
             ElmCode::Function(Function {
-                ref name,
-                ref type_signature,
+                name,
+                type_signature,
+                documentation,
             }) => exports.exports.push(ElmExport::Function {
                 name: String::from(*name),
                 type_signature: type_signature.clone(),
+                documentation: documentation.map(String::from),
+                fixity: fixities.get(name).cloned(),
+                port: false,
             }),
+            ElmCode::Port(port) => exports.exports.push(port_export(port)),
             // do nothing
             _ => {}
         }
     }
+    exports.exports.extend(operator_exports(elm_code, |_| true));
     exports
 }
+
+/// a port as a function with its `Cmd` or `Sub` type signature
+fn port_export(port: &Function) -> ElmExport {
+    ElmExport::Function {
+        name: String::from(port.name),
+        type_signature: port.type_signature.clone(),
+        documentation: port.documentation.map(String::from),
+        fixity: None,
+        port: true,
+    }
+}
+
+/// the fixities of the operators declared in the module, by operator
+fn fixities<'a>(elm_code: &[ElmCode<'a>]) -> HashMap<&'a str, Fixity> {
+    elm_code
+        .iter()
+        .filter_map(|code_bit| match code_bit {
+            ElmCode::Infix(Infix {
+                operator, fixity, ..
+            }) => Some((*operator, *fixity)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// operators standing for a function, `infix left 0 (|>) = apR`, as functions with the type
+/// signature and documentation of the function they stand for
+fn operator_exports<F>(elm_code: &[ElmCode], exported: F) -> Vec<ElmExport>
+where
+    F: Fn(&str) -> bool,
+{
+    elm_code
+        .iter()
+        .filter_map(|code_bit| match code_bit {
+            ElmCode::Infix(Infix {
+                operator,
+                fixity,
+                function: Some(function),
+            }) if exported(operator) => elm_code.iter().find_map(|code_bit| match code_bit {
+                ElmCode::Function(f) if f.name == *function => Some(ElmExport::Function {
+                    name: String::from(*operator),
+                    type_signature: f.type_signature.clone(),
+                    documentation: f.documentation.map(String::from),
+                    fixity: Some(*fixity),
+                    port: false,
+                }),
+                _ => None,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exported_functions(code: &str) -> Vec<(String, String)> {
+        get_elm_exports(code)
+            .expect("error parsing module")
+            .exports
+            .into_iter()
+            .filter_map(|export| match export {
+                ElmExport::Function {
+                    name,
+                    type_signature: Some(sig),
+                    ..
+                } => Some((name, sig.to_string())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, sig)| (name.to_string(), sig.to_string()))
+            .collect()
+    }
+
+    const TREE: &str = "\ntype Tree a\n    = Leaf\n    | Node (Tree a) a (Tree a)\n";
+
+    #[test]
+    fn exposed_constructors_are_functions() {
+        assert_eq!(
+            exported_functions(&format!("module Tree exposing (Tree(..))\n{}", TREE)),
+            pairs(&[
+                ("Leaf", "Tree a"),
+                ("Node", "Tree a -> a -> Tree a -> Tree a")
+            ])
+        );
+        assert_eq!(
+            exported_functions(&format!("module Tree exposing (..)\n{}", TREE)),
+            pairs(&[
+                ("Leaf", "Tree a"),
+                ("Node", "Tree a -> a -> Tree a -> Tree a")
+            ])
+        );
+    }
+
+    #[test]
+    fn opaque_types_hide_constructors() {
+        let code = format!(
+            "module Tree exposing (Tree, empty)\n{}\nempty : Tree a\nempty",
+            TREE
+        );
+        assert_eq!(exported_functions(&code), pairs(&[("empty", "Tree a")]));
+        let exports = get_elm_exports(&code).unwrap().exports;
+        match exports.first() {
+            Some(ElmExport::Type { name, .. }) => assert_eq!(name, "Tree"),
+            export => panic!("expected the type Tree, found {:?}", export),
+        }
+    }
+
+    #[test]
+    fn reports_declarations_which_cant_be_parsed() {
+        let code = "module A exposing (..)\n\nx : Int )\nx = 1\n\ny : Int\ny = 2\n";
+        let exports = get_elm_exports(code).expect("error parsing module");
+        assert_eq!(exported_functions(code), pairs(&[("y", "Int")]));
+        assert_eq!(exports.errors.len(), 1);
+        assert_eq!(exports.errors[0].construct, "type annotation");
+        assert_eq!((exports.errors[0].line, exports.errors[0].column), (3, 9));
+
+        let err = get_elm_exports("module A exposing (x\n\nx : Int\nx = 1\n").unwrap_err();
+        assert_eq!(err.construct, "module declaration");
+        assert_eq!(err.excerpt, "module A exposing (x");
+    }
+
+    fn fixity(code: &str, operator: &str) -> Option<Fixity> {
+        get_elm_exports(code)
+            .expect("error parsing module")
+            .exports
+            .into_iter()
+            .find_map(|export| match export {
+                ElmExport::Function { name, fixity, .. } if name == operator => fixity,
+                _ => None,
+            })
+    }
+
+    const BASICS: &str = "module Basics exposing ((|>), (<|), identity)
+
+infix right 0 (<|) = apL
+infix left  0 (|>) = apR
+
+{-| Saying `x |> f` is exactly the same as `f x`.
+-}
+apR : a -> (a -> b) -> b
+apR x f =
+  f x
+
+apL : (a -> b) -> a -> b
+apL f x =
+  f x
+
+identity : a -> a
+identity x =
+  x
+";
+
+    #[test]
+    fn infix_operators_have_the_type_of_their_function() {
+        assert_eq!(
+            exported_functions(BASICS),
+            pairs(&[
+                ("identity", "a -> a"),
+                ("<|", "(a -> b) -> a -> b"),
+                ("|>", "a -> (a -> b) -> b"),
+            ])
+        );
+        assert_eq!(
+            fixity(BASICS, "|>"),
+            Some(Fixity {
+                associativity: Associativity::Left,
+                precedence: 0
+            })
+        );
+        assert_eq!(fixity(BASICS, "identity"), None);
+    }
+
+    #[test]
+    fn operators_defined_directly() {
+        let code = "module Url.Parser exposing ((</>), top)
+
+infixr 7 </>
+
+(</>) : Parser a b -> Parser b c -> Parser a c
+(</>) (Parser parseBefore) (Parser parseAfter) =
+  Parser (parseBefore >> parseAfter)
+
+top : Parser a a
+top =
+  Parser identity
+";
+        assert_eq!(
+            exported_functions(code),
+            pairs(&[
+                ("</>", "Parser a b -> Parser b c -> Parser a c"),
+                ("top", "Parser a a"),
+            ])
+        );
+        assert_eq!(
+            fixity(code, "</>"),
+            Some(Fixity {
+                associativity: Associativity::Right,
+                precedence: 7
+            })
+        );
+    }
+
+    #[test]
+    fn exports_ports() {
+        let code = "port module Ports exposing (sendMessage, messageReceiver)
+
+port sendMessage : String -> Cmd msg
+
+port messageReceiver : (String -> msg) -> Sub msg
+
+port internal : () -> Cmd msg
+";
+        let exports = get_elm_exports(code).expect("error parsing module");
+        assert_eq!(exports.kind, ModuleKind::Port);
+        assert_eq!(
+            exported_functions(code),
+            pairs(&[
+                ("sendMessage", "String -> Cmd msg"),
+                ("messageReceiver", "(String -> msg) -> Sub msg"),
+            ])
+        );
+        assert!(exports.exports.iter().all(|export| match export {
+            ElmExport::Function { port, .. } => *port,
+            _ => false,
+        }));
+    }
+}
@@ -1,22 +1,30 @@
 use crate::helpers::{
-    is_allowed_for_types_and_functions, is_alphanumeric, is_operator, is_space_or_newline,
-    is_space_or_newline_or_comma,
+    is_allowed_for_types_and_functions, is_alphanumeric, is_module_name, is_operator, is_space,
+    is_space_or_newline, is_space_or_newline_or_comma,
 };
 
-use crate::structs::{ElmCode, ElmModule, Function, Type, TypeOrFunction};
+use crate::elm_type::ElmType;
+use crate::error::ParseError;
+use crate::layout::{annotated_name, defines, top_level_declarations};
+use crate::structs::{
+    Associativity, ElmCode, ElmModule, Fixity, Function, Import, Infix, ModuleDeclaration,
+    ModuleKind, Type, TypeDefinition, TypeOrFunction,
+};
+use nom::types::CompleteStr;
+use nom::{Context, Err, ErrorKind, IResult};
 
-named!(pub expose_all<&str, ElmModule>,
+named!(pub expose_all<&str, ElmModule<'_>>,
     map!(tag!(".."), |_| ElmModule::All)
 );
 
-named!(pub expose_functions_and_types<&str, ElmModule>,
+named!(pub expose_functions_and_types<&str, ElmModule<'_>>,
     map!(
         separated_list!(tag!(","), function_or_type),
         ElmModule::List
     )
 );
 
-named!(pub function_or_type<&str, TypeOrFunction>,
+named!(pub function_or_type<&str, TypeOrFunction<'_>>,
     map!(
         delimited!(
             many1!(
@@ -27,20 +35,21 @@ named!(pub function_or_type<&str, TypeOrFunction>,
                 )
             ),
             alt!(
+                // a type exposed along with its constructors, `Maybe(..)`
                 do_parse!(
                     s: take_while!(is_alphanumeric) >>
                     tag!("(") >>
                     take_while!(is_allowed_for_types_and_functions) >>
                     tag!(")") >>
-                    (s)
+                    ((s, true))
                 ) |
                 do_parse!(
                     tag!("(") >>
                     s: take_while!(is_operator) >>
                     tag!(")") >>
-                    (s)
+                    ((s, false))
                 ) |
-                take_while!(is_alphanumeric)
+                map!(take_while!(is_alphanumeric), |s| (s, false))
             ),
             many1!(
                 do_parse!(
@@ -50,24 +59,32 @@ named!(pub function_or_type<&str, TypeOrFunction>,
                 )
             )
         ),
-        |s| {
+        |(s, constructors)| {
             // based on the assumption that anything starting with:
             //      lowercase is a function
             //      uppcase is a type
-            if s.chars().next().unwrap_or_else(|| 'a').is_lowercase() {
+            //      an operator is a function
+            let first = s.chars().next().unwrap_or('a');
+            if first.is_lowercase() || is_operator(first) {
                 TypeOrFunction::Function(
                     Function{
                         name: s,
-                        type_signature: None
+                        type_signature: None,
+                        documentation: None
                     }
                 )
             } else {
-                TypeOrFunction::Type(
-                    Type{
-                        name: s,
-                        definition: None
-                    }
-                )
+                let typ = Type{
+                    name: s,
+                    parameters: vec![],
+                    definition: None,
+                    documentation: None
+                };
+                if constructors {
+                    TypeOrFunction::TypeWithConstructors(typ)
+                } else {
+                    TypeOrFunction::Type(typ)
+                }
             }
         }
     )
@@ -77,11 +94,7 @@ named!(pub multi_spaces_or_new_line_or_comma<&str, &str>,
     map!(take_while!(is_space_or_newline_or_comma), |s| s)
 );
 
-named!(pub ignore_any<&str, ElmCode>,
-    map!(take!(1), |_| ElmCode::Ignore)
-);
-
-named!(pub ignore_comments<&str, ElmCode>,
+named!(pub ignore_comments<&str, ElmCode<'_>>,
     map!(
         alt!(
             preceded!(tag!("{-"), take_until_and_consume!("-}")) |
@@ -101,100 +114,678 @@ named!(pub comments<&str, &str>,
     )
 );
 
+// whitespace, including newlines, along with any comments in it
+named!(space_or_comments<&str, &str>,
+    recognize!(many0!(alt!(comments | take_while1!(is_space_or_newline))))
+);
+
+// whitespace, including newlines, which may always appear between the tokens of a type
+named!(type_space<CompleteStr, CompleteStr>,
+    take_while!(is_space_or_newline)
+);
+
+/// whitespace which does not continue onto a line starting at column 0,
+/// since that line is the start of the next top level declaration
+fn indented_space(input: CompleteStr) -> IResult<CompleteStr, CompleteStr> {
+    let mut end = 0;
+    let mut chars = input.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\n' {
+            match chars.peek() {
+                Some((_, next)) if !is_space_or_newline(*next) => break,
+                _ => {}
+            }
+        } else if !is_space_or_newline(c) {
+            break;
+        }
+        end = i + c.len_utf8();
+    }
+    Ok((CompleteStr(&input[end..]), CompleteStr(&input[..end])))
+}
+
+named!(lower_name<CompleteStr, CompleteStr>,
+    verify!(
+        take_while1!(is_alphanumeric),
+        |s: CompleteStr| s.chars().next().is_some_and(char::is_lowercase)
+    )
+);
+
+named!(upper_name<CompleteStr, CompleteStr>,
+    verify!(
+        take_while1!(is_alphanumeric),
+        |s: CompleteStr| s.chars().next().is_some_and(char::is_uppercase)
+    )
+);
+
+// a type name with an optional module prefix, e.g. `Int` or `Json.Decode.Decoder`
+named!(qualified_upper_name<CompleteStr, CompleteStr>,
+    recognize!(
+        pair!(
+            upper_name,
+            many0!(pair!(char!('.'), upper_name))
+        )
+    )
+);
+
+named!(record_field<CompleteStr, (String, ElmType)>,
+    do_parse!(
+        name: lower_name >>
+        type_space >>
+        char!(':') >>
+        type_space >>
+        typ: type_annotation >>
+        ((name.0.to_string(), typ))
+    )
+);
+
+// `{}`, `{ x : Float, y : Float }` or `{ a | name : String }`
+named!(record_type<CompleteStr, ElmType>,
+    do_parse!(
+        char!('{') >>
+        type_space >>
+        base: opt!(
+            terminated!(
+                lower_name,
+                tuple!(type_space, char!('|'), type_space)
+            )
+        ) >>
+        fields: separated_list!(
+            tuple!(type_space, char!(','), type_space),
+            record_field
+        ) >>
+        type_space >>
+        char!('}') >>
+        (match base {
+            Some(base) => ElmType::ExtensibleRecord {
+                base: base.0.to_string(),
+                fields,
+            },
+            None => ElmType::Record(fields),
+        })
+    )
+);
+
+// `()`, `( type )` or `( type, type, ... )`
+named!(parenthesized_type<CompleteStr, ElmType>,
+    do_parse!(
+        char!('(') >>
+        type_space >>
+        types: separated_list!(
+            tuple!(type_space, char!(','), type_space),
+            type_annotation
+        ) >>
+        type_space >>
+        char!(')') >>
+        ({
+            let mut types = types;
+            match types.len() {
+                0 => ElmType::Unit,
+                1 => types.remove(0),
+                _ => ElmType::Tuple(types),
+            }
+        })
+    )
+);
+
+// a type which can be used as an argument to a type constructor without parentheses
+named!(type_term<CompleteStr, ElmType>,
+    alt!(
+        map!(lower_name, |name| ElmType::Variable(name.0.to_string())) |
+        map!(qualified_upper_name, |name| ElmType::Constructor {
+            name: name.0.to_string(),
+            args: vec![],
+        }) |
+        parenthesized_type |
+        record_type
+    )
+);
+
+// a type constructor along with its arguments, e.g. `Dict String (List a)`
+named!(type_application<CompleteStr, ElmType>,
+    alt!(
+        do_parse!(
+            name: qualified_upper_name >>
+            args: many0!(preceded!(indented_space, type_term)) >>
+            (ElmType::Constructor {
+                name: name.0.to_string(),
+                args,
+            })
+        ) |
+        type_term
+    )
+);
+
 /*
-    separate by -> ignore spaces, tabs, newline
-        name : type -> type -> type
-        name : type -> (type, type) -> type
+    a full type expression, functions are right associative
+        type -> type -> type
+        (type -> type) -> List type -> type
 */
-named!(pub function<&str, ElmCode>,
-    map!(
-        do_parse!(
-            tag!("\n") >>
-            name: take_while!(is_alphanumeric) >>
-            multi_spaces_or_new_line_or_comma >>
-            char!(':') >>
-            multi_spaces_or_new_line_or_comma >>
-            types: take_until!(name) >>
-            tag!(name) >>
-            (name, types)
-        ),
-        |(name, types)| {
-            let type_signature =
-                types
-                .split("->")
-                .map(|s| s.replace("\n", ""))
-                .map(|s| s.replace("\t", ""))
-                .map(|s| s.trim().to_string())
-                .collect::<Vec<String>>()
-                ;
-
-            ElmCode::Function(
-                Function{
-                    name: name,
-                    type_signature: Some(type_signature)
-                }
+named!(pub type_annotation<CompleteStr, ElmType>,
+    do_parse!(
+        arg: type_application >>
+        ret: opt!(
+            preceded!(
+                tuple!(indented_space, tag!("->"), type_space),
+                type_annotation
             )
-        }
+        ) >>
+        (match ret {
+            Some(ret) => ElmType::function(vec![arg], ret),
+            None => arg,
+        })
+    )
+);
+
+// a type annotation followed by nothing but whitespace
+named!(pub type_signature<CompleteStr, ElmType>,
+    do_parse!(
+        type_space >>
+        typ: type_annotation >>
+        type_space >>
+        eof!() >>
+        (typ)
+    )
+);
+
+/// runs the type annotation parser on the file contents
+fn function_type_signature(input: &str) -> IResult<&str, ElmType> {
+    match type_annotation(CompleteStr(input)) {
+        Ok((rest, typ)) => Ok((rest.0, typ)),
+        Err(_) => Err(Err::Error(error_position!(input, ErrorKind::Custom(0)))),
+    }
+}
+
+/*
+    name : type -> type -> type
+    name
+*/
+// the name of a function, or an operator in parentheses, `(|>)`
+named!(function_name<&str, &str>,
+    alt!(
+        recognize!(delimited!(char!('('), take_while1!(is_operator), char!(')'))) |
+        take_while!(is_alphanumeric)
+    )
+);
+
+named!(pub function<&str, ElmCode<'_>>,
+    do_parse!(
+        opt!(tag!("\n")) >>
+        name: function_name >>
+        multi_spaces_or_new_line_or_comma >>
+        char!(':') >>
+        multi_spaces_or_new_line_or_comma >>
+        sig: function_type_signature >>
+        space_or_comments >>
+        tag!(name) >>
+        (ElmCode::Function(
+            Function {
+                name: name.trim_start_matches('(').trim_end_matches(')'),
+                type_signature: Some(sig),
+                documentation: None,
+            }
+        ))
+    )
+);
+
+/*
+    port name : type -> Cmd msg
+*/
+named!(pub port_declaration<&str, ElmCode<'_>>,
+    do_parse!(
+        opt!(tag!("\n")) >>
+        tag!("port") >>
+        take_while1!(is_space_or_newline) >>
+        name: take_while1!(is_alphanumeric) >>
+        multi_spaces_or_new_line_or_comma >>
+        char!(':') >>
+        multi_spaces_or_new_line_or_comma >>
+        sig: function_type_signature >>
+        (ElmCode::Port(
+            Function {
+                name,
+                type_signature: Some(sig),
+                documentation: None,
+            }
+        ))
+    )
+);
+
+// whitespace separating two tokens of a declaration, which may continue onto indented lines
+named!(required_space<CompleteStr, CompleteStr>,
+    verify!(indented_space, |s: CompleteStr| !s.is_empty())
+);
+
+// a constructor of a custom type applied to its arguments, e.g. `Node (Tree a) (Tree a)`
+named!(type_constructor<CompleteStr, ElmType>,
+    do_parse!(
+        name: upper_name >>
+        args: many0!(preceded!(indented_space, type_term)) >>
+        (ElmType::Constructor {
+            name: name.0.to_string(),
+            args,
+        })
+    )
+);
+
+// the name and type variables of a type declaration, e.g. `Dict k v`
+named!(type_head<CompleteStr, (CompleteStr, Vec<CompleteStr>)>,
+    do_parse!(
+        name: upper_name >>
+        parameters: many0!(preceded!(required_space, lower_name)) >>
+        indented_space >>
+        char!('=') >>
+        indented_space >>
+        ((name, parameters))
+    )
+);
+
+/*
+    type alias Name a = type
+    type Name a = Constructor a | Constructor
+*/
+named!(type_declaration_body<CompleteStr, Type>,
+    do_parse!(
+        tag!("type") >>
+        required_space >>
+        declaration: alt!(
+            do_parse!(
+                tag!("alias") >>
+                required_space >>
+                head: type_head >>
+                aliased: type_annotation >>
+                ((head, TypeDefinition::Alias(aliased)))
+            ) |
+            do_parse!(
+                head: type_head >>
+                constructors: separated_nonempty_list!(
+                    tuple!(indented_space, char!('|'), indented_space),
+                    type_constructor
+                ) >>
+                ((head, TypeDefinition::Custom(constructors)))
+            )
+        ) >>
+        ({
+            let ((name, parameters), definition) = declaration;
+            Type {
+                name: name.0,
+                parameters: parameters.into_iter().map(|p| p.0).collect(),
+                definition: Some(definition),
+                documentation: None,
+            }
+        })
+    )
+);
+
+/// runs the type declaration parser on the file contents
+fn type_declaration_code(input: &str) -> IResult<&str, Type<'_>> {
+    match type_declaration_body(CompleteStr(input)) {
+        Ok((rest, typ)) => Ok((rest.0, typ)),
+        Err(_) => Err(Err::Error(error_position!(input, ErrorKind::Custom(1)))),
+    }
+}
+
+named!(pub type_declaration<&str, ElmCode<'_>>,
+    do_parse!(
+        opt!(tag!("\n")) >>
+        typ: type_declaration_code >>
+        (ElmCode::Type(typ))
+    )
+);
+
+named!(associativity<&str, Associativity>,
+    alt!(
+        value!(Associativity::Left, tag!("left")) |
+        value!(Associativity::Right, tag!("right")) |
+        value!(Associativity::Non, tag!("non"))
+    )
+);
+
+named!(precedence<&str, u8>,
+    map_res!(take_while1!(|c: char| c.is_ascii_digit()), str::parse)
+);
+
+/*
+    infix left 0 (|>) = apR
+
+    or the elm 0.18 form, for operators defined directly
+
+    infixl 0 |>
+*/
+named!(pub infix_declaration<&str, ElmCode<'_>>,
+    do_parse!(
+        opt!(tag!("\n")) >>
+        infix: alt!(
+            do_parse!(
+                tag!("infix") >>
+                take_while1!(is_space) >>
+                associativity: associativity >>
+                take_while1!(is_space) >>
+                precedence: precedence >>
+                take_while1!(is_space) >>
+                operator: delimited!(char!('('), take_while1!(is_operator), char!(')')) >>
+                take_while!(is_space) >>
+                char!('=') >>
+                take_while!(is_space) >>
+                function: take_while1!(is_alphanumeric) >>
+                (Infix {
+                    operator,
+                    fixity: Fixity { associativity, precedence },
+                    function: Some(function),
+                })
+            ) |
+            do_parse!(
+                associativity: alt!(
+                    value!(Associativity::Left, tag!("infixl")) |
+                    value!(Associativity::Right, tag!("infixr")) |
+                    value!(Associativity::Non, tag!("infix"))
+                ) >>
+                take_while1!(is_space) >>
+                precedence: precedence >>
+                take_while1!(is_space) >>
+                operator: take_while1!(is_operator) >>
+                (Infix {
+                    operator,
+                    fixity: Fixity { associativity, precedence },
+                    function: None,
+                })
+            )
+        ) >>
+        (ElmCode::Infix(infix))
+    )
+);
+
+// the contents of a documentation comment, `{-| docs -}`
+named!(pub doc_comment<&str, &str>,
+    map!(
+        preceded!(tag!("{-|"), take_until_and_consume!("-}")),
+        str::trim
+    )
+);
+
+/*
+    {-| documentation -}
+    name : type -> type -> type
+    name
+
+    {-| documentation -}
+    type Name = Constructor
+
+    {-| documentation -}
+    port name : type -> Cmd msg
+*/
+named!(pub documented_declaration<&str, ElmCode<'_>>,
+    do_parse!(
+        documentation: doc_comment >>
+        take_while!(is_space_or_newline) >>
+        code: alt!(function | type_declaration | port_declaration) >>
+        (match code {
+            ElmCode::Function(f) => ElmCode::Function(Function {
+                documentation: Some(documentation),
+                ..f
+            }),
+            ElmCode::Port(f) => ElmCode::Port(Function {
+                documentation: Some(documentation),
+                ..f
+            }),
+            ElmCode::Type(t) => ElmCode::Type(Type {
+                documentation: Some(documentation),
+                ..t
+            }),
+            code => code,
+        })
+    )
+);
+
+// the documentation of a module, which must directly follow the module declaration
+named!(module_documentation<&str, ElmCode<'_>>,
+    map!(
+        preceded!(take_while!(is_space_or_newline), doc_comment),
+        ElmCode::ModuleDocumentation
+    )
+);
+
+// the keyword before `module`, if any
+named!(module_kind<&str, ModuleKind>,
+    alt!(
+        value!(ModuleKind::Port, terminated!(tag!("port"), take_while1!(is_space_or_newline))) |
+        value!(ModuleKind::Effect, terminated!(tag!("effect"), take_while1!(is_space_or_newline))) |
+        value!(ModuleKind::Normal)
     )
 );
 
-// fails when module appears in comments before module statements
-named!(pub elm_mod_def<&str, ElmModule>,
+/*
+    module Name exposing (..)
+    port module Name exposing (..)
+    effect module Name where { command = MyCmd } exposing (..)
+*/
+named!(pub elm_mod_def<&str, ModuleDeclaration<'_>>,
     do_parse!(
-        take_until!("module") >>
+        kind: module_kind >>
         tag!("module") >>
+        take_while!(is_space_or_newline) >>
+        name: take_while1!(is_module_name) >>
         take_until!("exposing") >>
         tag!("exposing") >>
         multi_spaces_or_new_line_or_comma >>
         char!('(') >>
         exposed: alt!(expose_all | expose_functions_and_types) >>
         char!(')') >>
-        (exposed)
+        (ModuleDeclaration {
+            kind,
+            name,
+            exposing: exposed,
+        })
     )
 );
 
-named!(pub elm<&str, (ElmModule, Vec<ElmCode>)>,
-    alt!(
-        complete!(
+/*
+    import Json.Decode
+    import Json.Decode as Decode exposing (Decoder, decodeString)
+*/
+named!(pub import<&str, ElmCode<'_>>,
+    do_parse!(
+        opt!(tag!("\n")) >>
+        tag!("import") >>
+        take_while1!(is_space_or_newline) >>
+        module: take_while1!(is_module_name) >>
+        alias: opt!(complete!(
             do_parse!(
-                exposed: elm_mod_def >>
-                defs: many0!(
-                        alt!(
-                            complete!(ignore_comments) |
-                            complete!(function) |
-                            complete!(ignore_any)
-                        )
-                ) >>
-                (
-                    exposed,
-                    defs.into_iter()
-                        .filter(|w| w != &ElmCode::Ignore)
-                        .collect::<Vec<ElmCode>>()
-                )
+                take_while1!(is_space_or_newline) >>
+                tag!("as") >>
+                take_while1!(is_space_or_newline) >>
+                alias: take_while1!(is_alphanumeric) >>
+                (alias)
             )
-        )
-        |
-        complete!(
+        )) >>
+        exposing: opt!(complete!(
             do_parse!(
-                defs: many0!(alt!(
-                        complete!(ignore_comments) |
-                        complete!(function) |
-                        complete!(ignore_any)
-                    )) >>
-                (
-                    ElmModule::List(vec!()),
-                    defs.into_iter()
-                        .filter(|w| w != &ElmCode::Ignore).collect::<Vec<ElmCode>>()
-                )
+                take_while1!(is_space_or_newline) >>
+                tag!("exposing") >>
+                multi_spaces_or_new_line_or_comma >>
+                char!('(') >>
+                exposed: alt!(expose_all | expose_functions_and_types) >>
+                char!(')') >>
+                (exposed)
             )
-        )
+        )) >>
+        (ElmCode::Import(Import {
+            module,
+            alias,
+            exposing,
+        }))
     )
 );
 
+type DeclarationParser = for<'a> fn(&'a str) -> IResult<&'a str, ElmCode<'a>>;
+
+/// the parser for a top level declaration, chosen by how the declaration starts, along with the
+/// name of what it parses
+fn declaration_parser(declaration: &str) -> Option<(&'static str, DeclarationParser)> {
+    let keyword = declaration
+        .split(|c: char| c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+    match keyword {
+        "import" => Some(("import", import)),
+        "type" => Some(("type declaration", type_declaration)),
+        "infix" | "infixl" | "infixr" => Some(("infix declaration", infix_declaration)),
+        "port" => Some(("port declaration", port_declaration)),
+        _ if annotated_name(declaration).is_some() => Some(("type annotation", function)),
+        _ => None,
+    }
+}
+
+/// the offset in code of the position a parser of input failed at, errors without a position
+/// are at the start of input
+fn error_offset(code: &str, input: &str, e: &Err<&str>) -> usize {
+    let offset = |s: &str| (s.as_ptr() as usize).wrapping_sub(code.as_ptr() as usize);
+    match e {
+        Err::Error(Context::Code(rest, _)) | Err::Failure(Context::Code(rest, _))
+            if offset(rest) <= code.len() =>
+        {
+            offset(rest)
+        }
+        _ => offset(input),
+    }
+}
+
+/// true if declaration is `module`, `port module` or `effect module` followed by whitespace
+fn is_module_declaration(declaration: &str) -> bool {
+    let starts_with_keyword = |s: &str, keyword: &str| {
+        s.starts_with(keyword) && s[keyword.len()..].starts_with(char::is_whitespace)
+    };
+    let declaration = ["port", "effect"]
+        .iter()
+        .find(|keyword| starts_with_keyword(declaration, keyword))
+        .map_or(declaration, |keyword| {
+            declaration[keyword.len()..].trim_start()
+        });
+    starts_with_keyword(declaration, "module")
+}
+
+/// Parses an elm file one top level declaration at a time. A type annotation is only reported
+/// along with the definition following it, with nothing but comments in between, so annotations
+/// in `let` blocks, comments and strings are never reported. Declarations which can't be parsed are reported as
+/// `ElmCode::Unparsed`, the file fails to parse only if its module declaration can't be parsed
+pub fn elm(input: &str) -> Result<(ModuleDeclaration<'_>, Vec<ElmCode<'_>>), ParseError> {
+    let declarations = top_level_declarations(input);
+    let header = declarations
+        .iter()
+        .position(|r| is_module_declaration(&input[r.clone()]));
+    let (module, mut rest) = match header {
+        Some(i) => {
+            let declaration = &input[declarations[i].clone()];
+            match elm_mod_def(declaration) {
+                Ok((_, module)) => (module, &declarations[i + 1..]),
+                Err(e) => {
+                    let offset = error_offset(input, declaration, &e);
+                    return Err(ParseError::new(input, offset, "module declaration"));
+                }
+            }
+        }
+        None => (
+            // files without a module declaration are the Main module
+            ModuleDeclaration {
+                kind: ModuleKind::Normal,
+                name: "Main",
+                exposing: ElmModule::List(vec![]),
+            },
+            &declarations[..],
+        ),
+    };
+    let mut code = Vec::new();
+    if let Some(r) = rest.first() {
+        if let Ok((_, doc)) = module_documentation(&input[r.clone()]) {
+            code.push(doc);
+            rest = &rest[1..];
+        }
+    }
+    // the start of the documentation comment preceding the next declaration
+    let mut documentation = None;
+    let mut i = 0;
+    while i < rest.len() {
+        let declaration = &input[rest[i].clone()];
+        let (start, mut end) = (rest[i].start, rest[i].end);
+        i += 1;
+        if declaration.starts_with("{-|") {
+            documentation = Some(start);
+            continue;
+        }
+        if ignore_comments(declaration).is_ok() {
+            continue;
+        }
+        if let Some(name) = annotated_name(declaration) {
+            // comments may come between the annotation and the definition
+            let next = rest[i..]
+                .iter()
+                .position(|r| ignore_comments(&input[r.clone()]).is_err())
+                .map(|n| i + n);
+            match next.map(|n| (n, &rest[n])) {
+                Some((n, next)) if defines(&input[next.clone()], name) => {
+                    end = next.end;
+                    i = n + 1;
+                }
+                _ => {
+                    documentation = None;
+                    continue;
+                }
+            }
+        }
+        let (construct, parser) = match declaration_parser(declaration) {
+            Some(p) => p,
+            None => {
+                documentation = None;
+                continue;
+            }
+        };
+        if let Some((_, c)) = documentation
+            .take()
+            .and_then(|doc| documented_declaration(&input[doc..end]).ok())
+        {
+            code.push(c);
+            continue;
+        }
+        let declaration = &input[start..end];
+        code.push(match parser(declaration) {
+            Ok((_, c)) => c,
+            Err(e) => ElmCode::Unparsed(ParseError::new(
+                input,
+                error_offset(input, declaration, &e),
+                construct,
+            )),
+        });
+    }
+    Ok((module, code))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn con(name: &str, args: Vec<ElmType>) -> ElmType {
+        ElmType::Constructor {
+            name: name.to_string(),
+            args,
+        }
+    }
+
+    fn var(name: &str) -> ElmType {
+        ElmType::Variable(name.to_string())
+    }
+
+    fn func(args: Vec<ElmType>, ret: ElmType) -> ElmType {
+        ElmType::Function {
+            args,
+            ret: Box::new(ret),
+        }
+    }
+
+    fn parse(sig: &str) -> ElmType {
+        type_signature(CompleteStr(sig))
+            .expect("error parsing type signature")
+            .1
+    }
+
     #[test]
     fn multiline_comment() {
         assert_eq!(
@@ -211,23 +802,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn ignore_all() {
-        named!(test<&str, Vec<ElmCode>>, many1!(complete!(ignore_any)));
-        assert_eq!(
-            test("t s "),
-            Ok((
-                "",
-                vec!(
-                    ElmCode::Ignore,
-                    ElmCode::Ignore,
-                    ElmCode::Ignore,
-                    ElmCode::Ignore
-                )
-            ))
-        );
-    }
-
     #[test]
     fn function_type_signature() {
         assert_eq!(
@@ -236,10 +810,10 @@ mod tests {
                 "",
                 ElmCode::Function(Function {
                     name: "test",
-                    type_signature: Some(vec!(
-                        "Int".to_string(),
-                        "List Int".to_string(),
-                        "Int".to_string()
+                    documentation: None,
+                    type_signature: Some(func(
+                        vec!(con("Int", vec!()), con("List", vec!(con("Int", vec!())))),
+                        con("Int", vec!())
                     ))
                 })
             ))
@@ -247,166 +821,940 @@ mod tests {
     }
 
     #[test]
-    fn expose_all_works() {
+    fn higher_order_type_signature() {
         assert_eq!(
-            elm_mod_def("module Main exposing (..)"),
-            Ok(("", ElmModule::All))
+            parse("(a -> b) -> List a -> List b"),
+            func(
+                vec!(func(vec!(var("a")), var("b")), con("List", vec!(var("a")))),
+                con("List", vec!(var("b")))
+            )
         );
     }
 
     #[test]
-    fn expose_one_type_works() {
+    fn nested_type_constructors() {
         assert_eq!(
-            elm_mod_def("module Main exposing (test0)"),
-            Ok((
-                "",
-                ElmModule::List(vec!(TypeOrFunction::Function(Function {
-                    name: "test0",
-                    type_signature: None
-                }),))
-            ))
+            parse("Dict.Dict comparable (Maybe (List a))"),
+            con(
+                "Dict.Dict",
+                vec!(
+                    var("comparable"),
+                    con("Maybe", vec!(con("List", vec!(var("a")))))
+                )
+            )
         );
     }
 
     #[test]
-    fn expose_many_types_works() {
+    fn tuple_and_unit_types() {
         assert_eq!(
-            elm_mod_def("module Main exposing (Test0, test1)"),
-            Ok((
-                "",
-                ElmModule::List(vec!(
-                    TypeOrFunction::Type(Type {
-                        name: "Test0",
-                        definition: None
-                    }),
-                    TypeOrFunction::Function(Function {
-                        name: "test1",
-                        type_signature: None
-                    }),
-                ))
-            ))
+            parse("( a, Int ) -> ()"),
+            func(
+                vec!(ElmType::Tuple(vec!(var("a"), con("Int", vec!())))),
+                ElmType::Unit
+            )
         );
+        assert_eq!(parse("(Int)"), con("Int", vec!()));
     }
 
     #[test]
-    fn newline_separator() {
+    fn record_types() {
         assert_eq!(
-            elm_mod_def("module Utils.Time\n   exposing\n  ( a\n , b\n , c\n , d\n   )"),
-            Ok((
-                "",
-                ElmModule::List(vec!(
-                    TypeOrFunction::Function(Function {
-                        name: "a",
-                        type_signature: None
-                    }),
-                    TypeOrFunction::Function(Function {
-                        name: "b",
-                        type_signature: None
-                    }),
-                    TypeOrFunction::Function(Function {
-                        name: "c",
-                        type_signature: None
-                    }),
-                    TypeOrFunction::Function(Function {
-                        name: "d",
-                        type_signature: None
-                    }),
-                ))
-            ))
+            parse("{ x : Float, y : Float } -> Float"),
+            func(
+                vec!(ElmType::Record(vec!(
+                    ("x".to_string(), con("Float", vec!())),
+                    ("y".to_string(), con("Float", vec!()))
+                ))),
+                con("Float", vec!())
+            )
+        );
+        assert_eq!(
+            parse("{ a | name : String } -> String"),
+            func(
+                vec!(ElmType::ExtensibleRecord {
+                    base: "a".to_string(),
+                    fields: vec!(("name".to_string(), con("String", vec!())))
+                }),
+                con("String", vec!())
+            )
         );
+        assert_eq!(parse("{}"), ElmType::Record(vec!()));
     }
 
     #[test]
-    fn integration() {
-        assert_eq!(
-            elm("module Utils exposing (test)\ntest : Int -> List Int -> Int\ntest"),
+    fn parenthesized_return_is_flattened() {
+        assert_eq!(parse("a -> (b -> c)"), parse("a -> b -> c"));
+    }
+
+    #[test]
+    fn invalid_type_signature() {
+        assert!(type_signature(CompleteStr("List a ->")).is_err());
+        assert!(type_signature(CompleteStr("(a, b")).is_err());
+        assert!(type_signature(CompleteStr("Int Int)")).is_err());
+    }
+
+    #[test]
+    fn type_signature_display() {
+        for sig in &[
+            "(a -> b) -> List a -> List b",
+            "Dict.Dict comparable (Maybe (List a))",
+            "( a, Int ) -> ()",
+            "{ x : Float, y : Float } -> Float",
+            "{ a | name : String } -> String",
+        ] {
+            assert_eq!(&parse(sig).to_string(), sig);
+        }
+    }
+
+    #[test]
+    fn multiline_type_signature() {
+        assert_eq!(
+            function("\nfoldl :\n    (a -> b -> b)\n    -> b\n    -> List a\n    -> b\nfoldl"),
             Ok((
                 "",
-                (
-                    ElmModule::List(vec!(TypeOrFunction::Function(Function {
-                        name: "test",
+                ElmCode::Function(Function {
+                    name: "foldl",
+                    documentation: None,
+                    type_signature: Some(parse("(a -> b -> b) -> b -> List a -> b"))
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn type_signature_stops_at_column_zero() {
+        assert_eq!(
+            function("\nsingleton : a -> Maybe a\nsingleton"),
+            Ok((
+                "",
+                ElmCode::Function(Function {
+                    name: "singleton",
+                    documentation: None,
+                    type_signature: Some(func(vec!(var("a")), con("Maybe", vec!(var("a")))))
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn expose_all_works() {
+        assert_eq!(
+            elm_mod_def("module Main exposing (..)"),
+            Ok((
+                "",
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Main",
+                    exposing: ElmModule::All
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn expose_one_type_works() {
+        assert_eq!(
+            elm_mod_def("module Main exposing (test0)"),
+            Ok((
+                "",
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Main",
+                    exposing: ElmModule::List(vec!(TypeOrFunction::Function(Function {
+                        name: "test0",
+                        documentation: None,
                         type_signature: None
-                    }))),
-                    vec!(ElmCode::Function(Function {
-                        name: "test",
-                        type_signature: Some(vec!(
-                            "Int".to_string(),
-                            "List Int".to_string(),
-                            "Int".to_string()
-                        ))
-                    }))
-                )
+                    }),))
+                }
             ))
         );
     }
 
-    use std::fs;
+    #[test]
+    fn expose_many_types_works() {
+        assert_eq!(
+            elm_mod_def("module Main exposing (Test0, test1)"),
+            Ok((
+                "",
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Main",
+                    exposing: ElmModule::List(vec!(
+                        TypeOrFunction::Type(Type {
+                            name: "Test0",
+                            parameters: vec!(),
+                            documentation: None,
+                            definition: None
+                        }),
+                        TypeOrFunction::Function(Function {
+                            name: "test1",
+                            documentation: None,
+                            type_signature: None
+                        }),
+                    ))
+                }
+            ))
+        );
+    }
 
     #[test]
-    fn file_integration() {
-        let contents =
-            fs::read_to_string("Main.elm").expect("Something went wrong reading the file");
+    fn expose_constructors_works() {
+        assert_eq!(
+            elm_mod_def("module Maybe exposing (Maybe(..), map)"),
+            Ok((
+                "",
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Maybe",
+                    exposing: ElmModule::List(vec!(
+                        TypeOrFunction::TypeWithConstructors(Type {
+                            name: "Maybe",
+                            parameters: vec!(),
+                            documentation: None,
+                            definition: None
+                        }),
+                        TypeOrFunction::Function(Function {
+                            name: "map",
+                            documentation: None,
+                            type_signature: None
+                        }),
+                    ))
+                }
+            ))
+        );
+    }
 
+    #[test]
+    fn import_declarations() {
         assert_eq!(
-            elm(&contents),
+            import("\nimport Json.Decode as Decode exposing (Decoder, field)\nrest"),
+            Ok((
+                "\nrest",
+                ElmCode::Import(Import {
+                    module: "Json.Decode",
+                    alias: Some("Decode"),
+                    exposing: Some(ElmModule::List(vec!(
+                        TypeOrFunction::Type(Type {
+                            name: "Decoder",
+                            parameters: vec!(),
+                            documentation: None,
+                            definition: None
+                        }),
+                        TypeOrFunction::Function(Function {
+                            name: "field",
+                            documentation: None,
+                            type_signature: None
+                        })
+                    )))
+                })
+            ))
+        );
+        assert_eq!(
+            import("\nimport Html.Events\n"),
+            Ok((
+                "\n",
+                ElmCode::Import(Import {
+                    module: "Html.Events",
+                    alias: None,
+                    exposing: None
+                })
+            ))
+        );
+        assert_eq!(
+            import("\nimport Dict exposing (..)"),
             Ok((
                 "",
-                (
-                    ElmModule::All,
-                    vec!(
-                        ElmCode::Function(Function {
-                            name: "subscriptions",
-                            type_signature: Some(vec!("Model".to_string(), "Sub Msg".to_string(),))
+                ElmCode::Import(Import {
+                    module: "Dict",
+                    alias: None,
+                    exposing: Some(ElmModule::All)
+                })
+            ))
+        );
+        assert!(import("\nimportant : Int").is_err());
+    }
+
+    #[test]
+    fn infix_declarations() {
+        assert_eq!(
+            infix_declaration("\ninfix left  0 (|>) = apR\n"),
+            Ok((
+                "\n",
+                ElmCode::Infix(Infix {
+                    operator: "|>",
+                    fixity: Fixity {
+                        associativity: Associativity::Left,
+                        precedence: 0
+                    },
+                    function: Some("apR")
+                })
+            ))
+        );
+        assert_eq!(
+            infix_declaration("\ninfixr 5 ::\n"),
+            Ok((
+                "\n",
+                ElmCode::Infix(Infix {
+                    operator: "::",
+                    fixity: Fixity {
+                        associativity: Associativity::Right,
+                        precedence: 5
+                    },
+                    function: None
+                })
+            ))
+        );
+        assert!(infix_declaration("\ninfixes : List Int\n").is_err());
+    }
+
+    #[test]
+    fn operator_function() {
+        assert_eq!(
+            function("\n(|=) : Parser (a -> b) -> Parser a -> Parser b\n(|=) parseFunc"),
+            Ok((
+                " parseFunc",
+                ElmCode::Function(Function {
+                    name: "|=",
+                    documentation: None,
+                    type_signature: Some(parse("Parser (a -> b) -> Parser a -> Parser b"))
+                })
+            ))
+        );
+        assert_eq!(
+            elm_mod_def("module Parser exposing ((|.))"),
+            Ok((
+                "",
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Parser",
+                    exposing: ElmModule::List(vec!(TypeOrFunction::Function(Function {
+                        name: "|.",
+                        documentation: None,
+                        type_signature: None
+                    }),))
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn module_name() {
+        let name = |code| elm(code).map(|(module, _)| module.name);
+        assert_eq!(
+            name("{- comment -}\nmodule Json.Decode exposing (..)\n"),
+            Ok("Json.Decode")
+        );
+        // files without a module declaration are the Main module
+        assert_eq!(name("\nx : Int\nx = 1\n"), Ok("Main"));
+    }
+
+    #[test]
+    fn module_kinds() {
+        let header = |code| elm(code).map(|(module, _)| (module.kind, module.name));
+        assert_eq!(
+            header("module Json.Decode exposing (..)\n"),
+            Ok((ModuleKind::Normal, "Json.Decode"))
+        );
+        assert_eq!(
+            header("port module Ports exposing (..)\n"),
+            Ok((ModuleKind::Port, "Ports"))
+        );
+        assert_eq!(
+            header("effect module Task where { command = MyCmd } exposing\n  ( Task, perform )\n"),
+            Ok((ModuleKind::Effect, "Task"))
+        );
+        assert_eq!(
+            header("effect module Time where { subscription = MySub } exposing (..)\n"),
+            Ok((ModuleKind::Effect, "Time"))
+        );
+        assert_eq!(
+            header("{- not a port module -}\nmodule Main exposing (..)\n"),
+            Ok((ModuleKind::Normal, "Main"))
+        );
+    }
+
+    #[test]
+    fn port_declarations() {
+        assert_eq!(
+            port_declaration("port sendMessage : String -> Cmd msg"),
+            Ok((
+                "",
+                ElmCode::Port(Function {
+                    name: "sendMessage",
+                    documentation: None,
+                    type_signature: Some(parse("String -> Cmd msg"))
+                })
+            ))
+        );
+        let code = "port module Ports exposing (..)\n\n{-| ports -}\n\n\
+                    {-| receive messages -}\n\
+                    port messageReceiver :\n    (String -> msg) -> Sub msg\n";
+        assert_eq!(
+            elm(code).map(|(_, code)| code),
+            Ok(vec![
+                ElmCode::ModuleDocumentation("ports"),
+                ElmCode::Port(Function {
+                    name: "messageReceiver",
+                    documentation: Some("receive messages"),
+                    type_signature: Some(parse("(String -> msg) -> Sub msg"))
+                })
+            ])
+        );
+    }
+
+    #[test]
+    fn newline_separator() {
+        assert_eq!(
+            elm_mod_def("module Utils.Time\n   exposing\n  ( a\n , b\n , c\n , d\n   )"),
+            Ok((
+                "",
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Utils.Time",
+                    exposing: ElmModule::List(vec!(
+                        TypeOrFunction::Function(Function {
+                            name: "a",
+                            documentation: None,
+                            type_signature: None
                         }),
-                        ElmCode::Function(Function {
-                            name: "init",
-                            type_signature: Some(vec!(
-                                "Int".to_string(),
-                                "( Model, Cmd Msg )".to_string(),
-                            ))
+                        TypeOrFunction::Function(Function {
+                            name: "b",
+                            documentation: None,
+                            type_signature: None
                         }),
-                        ElmCode::Function(Function {
-                            name: "update",
-                            type_signature: Some(vec!(
-                                "Msg".to_string(),
-                                "Model".to_string(),
-                                "( Model, Cmd Msg )".to_string(),
-                            ))
+                        TypeOrFunction::Function(Function {
+                            name: "c",
+                            documentation: None,
+                            type_signature: None
                         }),
-                        ElmCode::Function(Function {
-                            name: "functionView",
-                            type_signature: Some(vec!(
-                                "SearchResult".to_string(),
-                                "Html Msg".to_string(),
-                            ))
+                        TypeOrFunction::Function(Function {
+                            name: "d",
+                            documentation: None,
+                            type_signature: None
                         }),
-                        ElmCode::Function(Function {
-                            name: "view",
-                            type_signature: Some(
-                                vec!("Model".to_string(), "Html Msg".to_string(),)
+                    ))
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn integration() {
+        assert_eq!(
+            elm("module Utils exposing (test)\ntest : Int -> List Int -> Int\ntest"),
+            Ok((
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Utils",
+                    exposing: ElmModule::List(vec!(TypeOrFunction::Function(Function {
+                        name: "test",
+                        documentation: None,
+                        type_signature: None
+                    })))
+                },
+                vec!(ElmCode::Function(Function {
+                    name: "test",
+                    documentation: None,
+                    type_signature: Some(func(
+                        vec!(con("Int", vec!()), con("List", vec!(con("Int", vec!())))),
+                        con("Int", vec!())
+                    ))
+                }))
+            ))
+        );
+    }
+
+    #[test]
+    fn type_alias_declaration() {
+        assert_eq!(
+            type_declaration("\ntype alias Point =\n    { x : Float, y : Float }\n\nrest"),
+            Ok((
+                "\n\nrest",
+                ElmCode::Type(Type {
+                    name: "Point",
+                    parameters: vec!(),
+                    documentation: None,
+                    definition: Some(TypeDefinition::Alias(ElmType::Record(vec!(
+                        (String::from("x"), con("Float", vec!())),
+                        (String::from("y"), con("Float", vec!()))
+                    ))))
+                })
+            ))
+        );
+        assert_eq!(
+            type_declaration("\ntype alias Decoder a = Value -> Result Error a"),
+            Ok((
+                "",
+                ElmCode::Type(Type {
+                    name: "Decoder",
+                    parameters: vec!("a"),
+                    documentation: None,
+                    definition: Some(TypeDefinition::Alias(func(
+                        vec!(con("Value", vec!())),
+                        con("Result", vec!(con("Error", vec!()), var("a")))
+                    )))
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn custom_type_declaration() {
+        assert_eq!(
+            type_declaration("\ntype Tree a\n    = Leaf\n    | Node (Tree a) a (Tree a)\n\nrest"),
+            Ok((
+                "\n\nrest",
+                ElmCode::Type(Type {
+                    name: "Tree",
+                    parameters: vec!("a"),
+                    documentation: None,
+                    definition: Some(TypeDefinition::Custom(vec!(
+                        con("Leaf", vec!()),
+                        con(
+                            "Node",
+                            vec!(
+                                con("Tree", vec!(var("a"))),
+                                var("a"),
+                                con("Tree", vec!(var("a")))
                             )
+                        )
+                    )))
+                })
+            ))
+        );
+        assert_eq!(
+            type_declaration("\ntype Dict k v = Dict (List ( k, v ))"),
+            Ok((
+                "",
+                ElmCode::Type(Type {
+                    name: "Dict",
+                    parameters: vec!("k", "v"),
+                    documentation: None,
+                    definition: Some(TypeDefinition::Custom(vec!(con(
+                        "Dict",
+                        vec!(con("List", vec!(ElmType::Tuple(vec!(var("k"), var("v"))))))
+                    ))))
+                })
+            ))
+        );
+        assert!(type_declaration("\ntype Empty =\n").is_err());
+    }
+
+    #[test]
+    fn type_definition_display() {
+        let definition = |code| match type_declaration(code) {
+            Ok((
+                _,
+                ElmCode::Type(Type {
+                    definition: Some(d),
+                    ..
+                }),
+            )) => (d.kind(), d.to_string()),
+            res => panic!("not a type declaration: {:?}", res),
+        };
+        assert_eq!(
+            definition("\ntype Maybe a = Just a | Nothing"),
+            ("custom", String::from("Just a | Nothing"))
+        );
+        assert_eq!(
+            definition("\ntype alias Pair a = ( a,   a )"),
+            ("alias", String::from("( a, a )"))
+        );
+    }
+
+    #[test]
+    fn doc_comment_contents() {
+        assert_eq!(
+            doc_comment("{-| Convert a list.\n\n    map sqrt [1,4]\n-}\nrest"),
+            Ok(("\nrest", "Convert a list.\n\n    map sqrt [1,4]"))
+        );
+        assert!(doc_comment("{- not docs -}").is_err());
+    }
+
+    #[test]
+    fn documented_function_integration() {
+        assert_eq!(
+            elm("module Utils exposing (test, other)\n\n{-| Utilities\n\n@docs test\n-}\n\nimport List\n\n{-| Does a test. -}\ntest : Int\ntest = 1\n\n{-| Not a function -}\ntype alias T = Int\n\nother : Int\nother = 2"),
+            Ok((
+                    ModuleDeclaration { kind: ModuleKind::Normal, name: "Utils", exposing: ElmModule::List(vec!(
+                        TypeOrFunction::Function(Function {
+                            name: "test",
+                            documentation: None,
+                            type_signature: None
                         }),
-                        ElmCode::Function(Function {
-                            name: "searchResultDecoder",
-                            type_signature: Some(vec!(
-                                "Decode.Decoder (List SearchResult)".to_string(),
-                            ))
+                        TypeOrFunction::Function(Function {
+                            name: "other",
+                            documentation: None,
+                            type_signature: None
+                        })
+                    )) },
+                    vec!(
+                        ElmCode::ModuleDocumentation("Utilities\n\n@docs test"),
+                        ElmCode::Import(Import {
+                            module: "List",
+                            alias: None,
+                            exposing: None
                         }),
                         ElmCode::Function(Function {
-                            name: "repoDecoder",
-                            type_signature: Some(vec!(
-                                "Decode.Decoder SearchResultRepo".to_string(),
-                            ))
+                            name: "test",
+                            documentation: Some("Does a test."),
+                            type_signature: Some(con("Int", vec!()))
                         }),
-                        ElmCode::Function(Function {
-                            name: "resDecoder",
-                            type_signature: Some(
-                                vec!("Decode.Decoder SearchResultFn".to_string(),)
-                            )
+                        ElmCode::Type(Type {
+                            name: "T",
+                            parameters: vec!(),
+                            documentation: Some("Not a function"),
+                            definition: Some(TypeDefinition::Alias(con("Int", vec!())))
                         }),
+                        ElmCode::Function(Function {
+                            name: "other",
+                            documentation: None,
+                            type_signature: Some(con("Int", vec!()))
+                        })
                     )
+            ))
+        );
+    }
+
+    use std::fs;
+
+    #[test]
+    fn file_integration() {
+        let contents =
+            fs::read_to_string("Main.elm").expect("Something went wrong reading the file");
+
+        assert_eq!(
+            elm(&contents),
+            Ok((
+                ModuleDeclaration {
+                    kind: ModuleKind::Normal,
+                    name: "Main",
+                    exposing: ElmModule::All
+                },
+                vec!(
+                    ElmCode::Import(Import {
+                        module: "Browser",
+                        alias: None,
+                        exposing: None
+                    }),
+                    ElmCode::Import(Import {
+                        module: "Html",
+                        alias: None,
+                        exposing: Some(ElmModule::List(vec!(
+                            TypeOrFunction::Type(Type {
+                                name: "Html",
+                                parameters: vec!(),
+                                documentation: None,
+                                definition: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "button",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "div",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "text",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "input",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "a",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "header",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "p",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "hr",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                        )))
+                    }),
+                    ElmCode::Import(Import {
+                        module: "Html.Attributes",
+                        alias: None,
+                        exposing: Some(ElmModule::List(vec!(
+                            TypeOrFunction::Function(Function {
+                                name: "placeholder",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "value",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "class",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "href",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                        )))
+                    }),
+                    ElmCode::Import(Import {
+                        module: "Html.Events",
+                        alias: None,
+                        exposing: Some(ElmModule::List(vec!(
+                            TypeOrFunction::Function(Function {
+                                name: "onClick",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                            TypeOrFunction::Function(Function {
+                                name: "onInput",
+                                documentation: None,
+                                type_signature: None
+                            }),
+                        )))
+                    }),
+                    ElmCode::Import(Import {
+                        module: "Json.Decode",
+                        alias: Some("Decode"),
+                        exposing: None
+                    }),
+                    ElmCode::Import(Import {
+                        module: "Http",
+                        alias: None,
+                        exposing: None
+                    }),
+                    ElmCode::Function(Function {
+                        name: "subscriptions",
+                        documentation: None,
+                        type_signature: Some(func(
+                            vec!(con("Model", vec!())),
+                            con("Sub", vec!(con("Msg", vec!())))
+                        ))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "init",
+                        documentation: None,
+                        type_signature: Some(func(
+                            vec!(con("Int", vec!())),
+                            ElmType::Tuple(vec!(
+                                con("Model", vec!()),
+                                con("Cmd", vec!(con("Msg", vec!())))
+                            ))
+                        ))
+                    }),
+                    ElmCode::Type(Type {
+                        name: "Msg",
+                        parameters: vec!(),
+                        documentation: None,
+                        definition: Some(TypeDefinition::Custom(vec!(
+                            con("Search", vec!()),
+                            con("UpdateQuery", vec!(con("String", vec!()))),
+                            con(
+                                "NewResult",
+                                vec!(con(
+                                    "Result",
+                                    vec!(
+                                        con("Http.Error", vec!()),
+                                        con("List", vec!(con("SearchResult", vec!())))
+                                    )
+                                ))
+                            )
+                        )))
+                    }),
+                    ElmCode::Type(Type {
+                        name: "TypeSignature",
+                        parameters: vec!(),
+                        documentation: None,
+                        definition: Some(TypeDefinition::Alias(con("String", vec!())))
+                    }),
+                    ElmCode::Type(Type {
+                        name: "Model",
+                        parameters: vec!(),
+                        documentation: None,
+                        definition: Some(TypeDefinition::Alias(ElmType::Record(vec!(
+                            (String::from("query"), con("TypeSignature", vec!())),
+                            (
+                                String::from("results"),
+                                con(
+                                    "Maybe",
+                                    vec!(con("List", vec!(con("SearchResult", vec!()))))
+                                )
+                            )
+                        ))))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "update",
+                        documentation: None,
+                        type_signature: Some(func(
+                            vec!(con("Msg", vec!()), con("Model", vec!())),
+                            ElmType::Tuple(vec!(
+                                con("Model", vec!()),
+                                con("Cmd", vec!(con("Msg", vec!())))
+                            ))
+                        ))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "functionView",
+                        documentation: None,
+                        type_signature: Some(func(
+                            vec!(con("SearchResult", vec!())),
+                            con("Html", vec!(con("Msg", vec!())))
+                        ))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "view",
+                        documentation: None,
+                        type_signature: Some(func(
+                            vec!(con("Model", vec!())),
+                            con("Html", vec!(con("Msg", vec!())))
+                        ))
+                    }),
+                    ElmCode::Type(Type {
+                        name: "SearchResultRepo",
+                        parameters: vec!(),
+                        documentation: None,
+                        definition: Some(TypeDefinition::Alias(ElmType::Record(vec!(
+                            (String::from("name"), con("String", vec!())),
+                            (String::from("url"), con("String", vec!()))
+                        ))))
+                    }),
+                    ElmCode::Type(Type {
+                        name: "SearchResultFn",
+                        parameters: vec!(),
+                        documentation: None,
+                        definition: Some(TypeDefinition::Alias(ElmType::Record(vec!(
+                            (String::from("name"), con("String", vec!())),
+                            (String::from("desc"), con("String", vec!())),
+                            (
+                                String::from("args"),
+                                con("List", vec!(con("String", vec!())))
+                            )
+                        ))))
+                    }),
+                    ElmCode::Type(Type {
+                        name: "SearchResult",
+                        parameters: vec!(),
+                        documentation: None,
+                        definition: Some(TypeDefinition::Alias(ElmType::Record(vec!(
+                            (String::from("repo"), con("SearchResultRepo", vec!())),
+                            (String::from("fn"), con("SearchResultFn", vec!()))
+                        ))))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "searchResultDecoder",
+                        documentation: None,
+                        type_signature: Some(con(
+                            "Decode.Decoder",
+                            vec!(con("List", vec!(con("SearchResult", vec!()))))
+                        ))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "repoDecoder",
+                        documentation: None,
+                        type_signature: Some(con(
+                            "Decode.Decoder",
+                            vec!(con("SearchResultRepo", vec!()))
+                        ))
+                    }),
+                    ElmCode::Function(Function {
+                        name: "resDecoder",
+                        documentation: None,
+                        type_signature: Some(con(
+                            "Decode.Decoder",
+                            vec!(con("SearchResultFn", vec!()))
+                        ))
+                    }),
                 )
             ))
         );
     }
+
+    // the names and documentation of the functions found in an elm file
+    fn functions(code: &str) -> Vec<(&str, Option<&str>)> {
+        let (_, code) = elm(code).expect("error parsing module");
+        code.into_iter()
+            .filter_map(|c| match c {
+                ElmCode::Function(Function {
+                    name,
+                    documentation,
+                    ..
+                }) => Some((name, documentation)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn skips_declarations_in_strings() {
+        let contents =
+            fs::read_to_string("NoDebugTest.elm").expect("error reading NoDebugTest.elm");
+        assert_eq!(functions(&contents), vec!(("all", None)));
+        let (_, code) = elm(&contents).unwrap();
+        assert!(!code.iter().any(|c| matches!(c, ElmCode::Type(_))));
+    }
+
+    #[test]
+    fn skips_comment_delimiters_in_strings() {
+        let contents = fs::read_to_string("Comment.elm").expect("error reading Comment.elm");
+        assert_eq!(
+            functions(&contents),
+            vec!(
+                (
+                    "commentStart",
+                    Some("Parse the start of a multi-line comment.")
+                ),
+                ("commentEnd", Some("Parse the end of a multi-line comment.")),
+                ("spaces", Some("Whitespace, including newlines.")),
+            )
+        );
+    }
+
+    #[test]
+    fn documentation_may_be_separated_by_blank_lines() {
+        assert_eq!(
+            functions(
+                "module A exposing (..)\n\n{-| A -}\n\n{-| doc -}\n\n\nf : Int -> Int\nf x = x\n"
+            ),
+            vec!(("f", Some("doc")))
+        );
+    }
+
+    #[test]
+    fn pairs_annotations_with_the_following_definition() {
+        assert_eq!(
+            functions("module A exposing (..)\n\nlength : Int\n\nwidth : Int\nwidth = 2\n"),
+            vec!(("width", None))
+        );
+        assert_eq!(
+            functions("module A exposing (..)\n\nmap : Int\n\nmapper = 1\n"),
+            vec!()
+        );
+        assert_eq!(
+            functions("module A exposing (..)\n\n{-| A -}\n\n{-| docs -}\nx : Int\n-- x\nx = 1\n"),
+            vec!(("x", Some("docs")))
+        );
+        assert_eq!(
+            functions("module A exposing (..)\n\nx : Int\n{- x -}\n\n-- y\nx = 1\n"),
+            vec!(("x", None))
+        );
+    }
 }
@@ -1,11 +1,21 @@
-#[derive(Debug, PartialEq)]
+use crate::elm_type::ElmType;
+use crate::error::ParseError;
+use std::fmt;
 
+#[derive(Debug, PartialEq)]
 pub enum ElmCode<'a> {
     Comment,
-    Declaration,
-    Ignore,
+    /// an operator declaration, `infix left 0 (|>) = apR`
+    Infix(Infix<'a>),
+    /// the documentation comment following the module declaration
+    ModuleDocumentation(&'a str),
+    Import(Import<'a>),
     Function(Function<'a>),
+    /// a port declaration, `port send : String -> Cmd msg`
+    Port(Function<'a>),
     Type(Type<'a>),
+    /// a top level declaration which couldn't be parsed
+    Unparsed(ParseError),
 }
 
 #[derive(Debug, PartialEq)]
@@ -14,24 +24,141 @@ pub enum ElmModule<'a> {
     List(Vec<TypeOrFunction<'a>>),
 }
 
+/// the kind of module a module declaration starts, from the keywords before `module`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModuleKind {
+    Normal,
+    /// `port module Ports exposing (..)`, a module which may declare ports
+    Port,
+    /// `effect module Task where { command = MyCmd } exposing (..)`, an effect manager
+    Effect,
+}
+
+impl fmt::Display for ModuleKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModuleKind::Normal => write!(f, "normal"),
+            ModuleKind::Port => write!(f, "port"),
+            ModuleKind::Effect => write!(f, "effect"),
+        }
+    }
+}
+
+/// the `module Name exposing (..)` declaration at the top of a file
+#[derive(Debug, PartialEq)]
+pub struct ModuleDeclaration<'a> {
+    pub kind: ModuleKind,
+    pub name: Name<'a>,
+    pub exposing: ElmModule<'a>,
+}
+
+/// an `import Json.Decode as Decode exposing (Decoder)` declaration
+#[derive(Debug, PartialEq)]
+pub struct Import<'a> {
+    pub module: Name<'a>,
+    pub alias: Option<Name<'a>>,
+    pub exposing: Option<ElmModule<'a>>,
+}
+
+/// the way an operator groups with operators of the same precedence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right,
+    Non,
+}
+
+impl fmt::Display for Associativity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Associativity::Left => write!(f, "left"),
+            Associativity::Right => write!(f, "right"),
+            Associativity::Non => write!(f, "non"),
+        }
+    }
+}
+
+/// how tightly an operator binds, `infix left 0 (|>)` is left associative with precedence 0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fixity {
+    pub associativity: Associativity,
+    pub precedence: u8,
+}
+
+/// an operator declaration, `infix left 0 (|>) = apR` or `infixl 0 |>`
+#[derive(Debug, PartialEq)]
+pub struct Infix<'a> {
+    /// the operator without parentheses, e.g. `|>`
+    pub operator: Name<'a>,
+    pub fixity: Fixity,
+    /// the function the operator stands for, `None` for operators defined directly,
+    /// `(|>) x f = f x`
+    pub function: Option<Name<'a>>,
+}
+
 type Name<'a> = &'a str;
-type Definition<'a> = &'a str;
-type TypeSignature = Vec<String>;
+type TypeSignature = ElmType;
+type Documentation<'a> = &'a str;
 
 #[derive(Debug, PartialEq)]
 pub enum TypeOrFunction<'a> {
     Type(Type<'a>),
+    /// a type exposed along with its constructors, `Maybe(..)`
+    TypeWithConstructors(Type<'a>),
     Function(Function<'a>),
 }
 
+/// the right hand side of a type declaration
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeDefinition {
+    /// `type alias Point = { x : Float, y : Float }`, the aliased type
+    Alias(ElmType),
+    /// `type Maybe a = Just a | Nothing`, the constructors applied to their arguments
+    Custom(Vec<ElmType>),
+    /// a custom type documented in `docs.json` without its constructors, which aren't exposed
+    Opaque,
+}
+
+impl TypeDefinition {
+    /// `alias` or `custom`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TypeDefinition::Alias(_) => "alias",
+            TypeDefinition::Custom(_) | TypeDefinition::Opaque => "custom",
+        }
+    }
+}
+
+impl fmt::Display for TypeDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TypeDefinition::Alias(typ) => write!(f, "{}", typ),
+            TypeDefinition::Custom(constructors) => {
+                for (i, constructor) in constructors.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, " | ")?;
+                    }
+                    write!(f, "{}", constructor)?;
+                }
+                Ok(())
+            }
+            TypeDefinition::Opaque => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Type<'a> {
     pub name: Name<'a>,
-    pub definition: Option<Definition<'a>>,
+    /// the names of the type variables the type takes, e.g. `a` in `Maybe a`
+    pub parameters: Vec<Name<'a>>,
+    pub definition: Option<TypeDefinition>,
+    pub documentation: Option<Documentation<'a>>,
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Function<'a> {
     pub name: Name<'a>,
     pub type_signature: Option<TypeSignature>,
+    pub documentation: Option<Documentation<'a>>,
 }
@@ -0,0 +1,235 @@
+use crate::elm_type::ElmType;
+use crate::structs::{ElmCode, ElmModule, Import, Type, TypeOrFunction};
+use hashbrown::HashMap;
+
+/// modules every elm module imports implicitly, by the name they are imported as
+const DEFAULT_MODULES: [(&str, &str); 11] = [
+    ("Basics", "Basics"),
+    ("List", "List"),
+    ("Maybe", "Maybe"),
+    ("Result", "Result"),
+    ("String", "String"),
+    ("Char", "Char"),
+    ("Tuple", "Tuple"),
+    ("Debug", "Debug"),
+    ("Platform", "Platform"),
+    ("Cmd", "Platform.Cmd"),
+    ("Sub", "Platform.Sub"),
+];
+
+/// types every elm module can refer to without a module prefix, along with their module
+const DEFAULT_TYPES: [(&str, &str); 13] = [
+    ("Basics", "Int"),
+    ("Basics", "Float"),
+    ("Basics", "Bool"),
+    ("Basics", "Order"),
+    ("Basics", "Never"),
+    ("List", "List"),
+    ("Maybe", "Maybe"),
+    ("Result", "Result"),
+    ("String", "String"),
+    ("Char", "Char"),
+    ("Platform", "Program"),
+    ("Platform.Cmd", "Cmd"),
+    ("Platform.Sub", "Sub"),
+];
+
+/// Resolves the names of types used in a module to the module they are defined in, using the
+/// imports of the module.
+///
+/// `Decode.Decoder` and `Decoder` both resolve to `Json.Decode.Decoder` in a module which
+/// imports `Json.Decode as Decode exposing (Decoder)`. Types every module imports implicitly,
+/// such as `Int` and `Maybe`, resolve to their unqualified name, so `Maybe.Maybe` is `Maybe`
+#[derive(Debug, Clone)]
+pub struct TypeScope {
+    /// fully qualified names of the types which can be used without a module prefix
+    types: HashMap<String, String>,
+    /// module names by the name or alias they are imported as
+    modules: HashMap<String, String>,
+}
+
+impl TypeScope {
+    /// the types available to every module, which are imported implicitly
+    pub fn new() -> Self {
+        TypeScope {
+            types: DEFAULT_TYPES
+                .iter()
+                .map(|(_, name)| (name.to_string(), name.to_string()))
+                .collect(),
+            modules: DEFAULT_MODULES
+                .iter()
+                .map(|(alias, module)| (alias.to_string(), module.to_string()))
+                .collect(),
+        }
+    }
+
+    /// the types available in the module called module_name with the given code
+    pub(crate) fn from_code(module_name: &str, code: &[ElmCode]) -> Self {
+        let mut scope = TypeScope::new();
+        for code_bit in code {
+            if let ElmCode::Import(import) = code_bit {
+                scope.import(import);
+            }
+        }
+        // types defined in the module take precedence over imported types
+        for code_bit in code {
+            if let ElmCode::Type(Type { name, .. }) = code_bit {
+                scope
+                    .types
+                    .insert(name.to_string(), qualify(module_name, name));
+            }
+        }
+        scope
+    }
+
+    fn import(&mut self, import: &Import) {
+        let Import {
+            module,
+            alias,
+            exposing,
+        } = import;
+        self.modules
+            .insert(alias.unwrap_or(module).to_string(), module.to_string());
+        // the types exposed by `exposing (..)` aren't known without parsing the imported module
+        if let Some(ElmModule::List(exposed)) = exposing {
+            for exposed in exposed {
+                if let TypeOrFunction::Type(Type { name, .. })
+                | TypeOrFunction::TypeWithConstructors(Type { name, .. }) = exposed
+                {
+                    self.types.insert(name.to_string(), qualify(module, name));
+                }
+            }
+        }
+    }
+
+    /// replaces the name of every type in typ with its fully qualified name, types which
+    /// can't be resolved are left alone
+    pub fn resolve(&self, typ: &ElmType) -> ElmType {
+        let resolve_fields = |fields: &[(String, ElmType)]| {
+            fields
+                .iter()
+                .map(|(name, typ)| (name.clone(), self.resolve(typ)))
+                .collect()
+        };
+        match typ {
+            ElmType::Constructor { name, args } => ElmType::Constructor {
+                name: self.resolve_name(name),
+                args: args.iter().map(|arg| self.resolve(arg)).collect(),
+            },
+            ElmType::Variable(_) | ElmType::Unit => typ.clone(),
+            ElmType::Function { args, ret } => ElmType::Function {
+                args: args.iter().map(|arg| self.resolve(arg)).collect(),
+                ret: Box::new(self.resolve(ret)),
+            },
+            ElmType::Tuple(types) => {
+                ElmType::Tuple(types.iter().map(|t| self.resolve(t)).collect())
+            }
+            ElmType::Record(fields) => ElmType::Record(resolve_fields(fields)),
+            ElmType::ExtensibleRecord { base, fields } => ElmType::ExtensibleRecord {
+                base: base.clone(),
+                fields: resolve_fields(fields),
+            },
+        }
+    }
+
+    fn resolve_name(&self, name: &str) -> String {
+        match name.rfind('.') {
+            Some(i) => {
+                let (prefix, name) = (&name[..i], &name[i + 1..]);
+                let module = self.modules.get(prefix).map_or(prefix, String::as_str);
+                qualify(module, name)
+            }
+            None => self
+                .types
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.to_string()),
+        }
+    }
+}
+
+impl Default for TypeScope {
+    fn default() -> Self {
+        TypeScope::new()
+    }
+}
+
+/// the fully qualified name of the type called name in module, types imported implicitly are
+/// left unqualified
+fn qualify(module: &str, name: &str) -> String {
+    if DEFAULT_TYPES.contains(&(module, name)) {
+        name.to_string()
+    } else {
+        format!("{}.{}", module, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TypeScope;
+    use crate::parse_type_signature;
+    use crate::parser::elm;
+
+    fn resolve(code: &str, sig: &str) -> String {
+        let (module, code) = elm(code).expect("error parsing module");
+        TypeScope::from_code(module.name, &code)
+            .resolve(&parse_type_signature(sig).expect("error parsing type signature"))
+            .to_string()
+    }
+
+    const IMPORTS: &str = "module Main exposing (..)
+
+import Html exposing (Html, div)
+import Json.Decode as Decode exposing (Decoder)
+import Http
+";
+
+    #[test]
+    fn resolves_imported_types() {
+        assert_eq!(
+            resolve(IMPORTS, "Decode.Decoder a -> Decoder a"),
+            "Json.Decode.Decoder a -> Json.Decode.Decoder a"
+        );
+        assert_eq!(
+            resolve(IMPORTS, "Json.Decode.Value -> Http.Error -> Html msg"),
+            "Json.Decode.Value -> Http.Error -> Html.Html msg"
+        );
+    }
+
+    #[test]
+    fn resolves_local_types() {
+        let code = format!("{}\ntype alias Model = {{ count : Int }}\n", IMPORTS);
+        assert_eq!(
+            resolve(&code, "Model -> Html Msg"),
+            "Main.Model -> Html.Html Msg"
+        );
+    }
+
+    #[test]
+    fn default_imports_are_unqualified() {
+        assert_eq!(
+            resolve(
+                IMPORTS,
+                "Maybe.Maybe Int -> List String -> Platform.Cmd.Cmd msg"
+            ),
+            "Maybe Int -> List String -> Cmd msg"
+        );
+        assert_eq!(
+            resolve(IMPORTS, "Cmd.Cmd msg -> Sub msg"),
+            "Cmd msg -> Sub msg"
+        );
+    }
+
+    #[test]
+    fn resolves_main_fixture() {
+        let contents = std::fs::read_to_string("Main.elm").expect("error reading Main.elm");
+        assert_eq!(
+            resolve(&contents, "Decode.Decoder (List SearchResult)"),
+            "Json.Decode.Decoder (List Main.SearchResult)"
+        );
+        assert_eq!(
+            resolve(&contents, "Msg -> Model -> ( Model, Cmd Msg )"),
+            "Main.Msg -> Main.Model -> ( Main.Model, Cmd Main.Msg )"
+        );
+    }
+}
@@ -1,4 +1,3 @@
-use crate::repo_cache::RepoCacheOptions;
 use crate::subprocess::{exec, ExecError};
 use fn_search_backend::Config;
 use std::process::Command;
@@ -6,9 +5,15 @@ use std::string::FromUtf8Error;
 use std::time::Duration;
 use std::{error::Error, fmt};
 
-pub fn chrome_dl(url: &str, config: &Config, o: &RepoCacheOptions) -> Result<String, ChromeError> {
+/// the html of the page at url once it has been rendered by the chromium executable at
+/// chromium_bin_path
+pub fn chrome_dl(
+    url: &str,
+    chromium_bin_path: &str,
+    config: &Config,
+) -> Result<String, ChromeError> {
     let res = exec(
-        &mut Command::new(o.chromium_bin_path.as_str()).args(&[
+        &mut Command::new(chromium_bin_path).args(&[
             "--headless",
             "--disable-gpu",
             "--dump-dom",
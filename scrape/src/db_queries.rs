@@ -1,6 +1,7 @@
+use crate::elm_json::PackageManifest;
 use crate::elm_package::ElmFile;
 use fn_search_backend::DbConfig;
-use fn_search_backend_parsers::ElmExport;
+use fn_search_backend_parsers::{ElmExport, TypeAliases, TypeDefinition};
 // import issues
 use fn_search_backend_db::{
     diesel::{self, prelude::*, PgConnection},
@@ -73,6 +74,43 @@ pub fn update_repo(
     Ok(())
 }
 
+/// store the summary, license, elm version and dependencies from the manifest of a package,
+/// replacing any stored before
+pub fn update_package_metadata(
+    cfg: &DbConfig,
+    repo_name: &str,
+    manifest: &PackageManifest,
+) -> Result<(), UpdateUrlError> {
+    let db_url = get_db_url(&cfg);
+    let conn = PgConnection::establish(db_url.as_str())?;
+    conn.transaction(|| -> Result<(), UpdateUrlError> {
+        let mut repos = repositories::table
+            .filter(repositories::name.eq(&repo_name))
+            .limit(1)
+            .load::<Repository>(&conn)?;
+        let mut repo = repos.pop().ok_or(UpdateUrlError::RepoNotFound)?;
+        repo.summary = Some(manifest.summary.clone());
+        repo.license = Some(manifest.license.clone());
+        repo.elm_version = Some(manifest.elm_version.clone());
+        repo.save_changes::<Repository>(&conn)?;
+        diesel::delete(dependencies::table.filter(dependencies::repo_id.eq(repo.id)))
+            .execute(&conn)?;
+        let new_dependencies: Vec<_> = manifest
+            .dependencies
+            .iter()
+            .map(|(package, constraint)| NewDependency {
+                repo_id: repo.id,
+                package: package.as_str(),
+                version_constraint: constraint.as_str(),
+            })
+            .collect();
+        diesel::insert_into(dependencies::table)
+            .values(new_dependencies.as_slice())
+            .execute(&conn)?;
+        Ok(())
+    })
+}
+
 pub fn insert_functions(
     cfg: &DbConfig,
     repo_name: &str,
@@ -89,28 +127,66 @@ pub fn insert_functions(
         Some(repo) => {
             let exports: Vec<_> = elm_files
                 .iter()
-                .map(|file| file.exports.exports.iter())
-                .flatten()
+                .flat_map(|file| {
+                    let file_exports = &file.exports;
+                    file_exports
+                        .exports
+                        .iter()
+                        .map(move |export| (file_exports, export))
+                })
                 .collect();
+            // the aliases of every module in the package by their qualified name, the names in
+            // resolved signatures are qualified by the module the type is defined in
+            let mut aliases = TypeAliases::new();
+            for (file_exports, export) in exports.iter() {
+                if let ElmExport::Type {
+                    name,
+                    parameters,
+                    definition: TypeDefinition::Alias(aliased),
+                    ..
+                } = export
+                {
+                    let aliased = file_exports.scope.resolve(aliased);
+                    let qualified = format!("{}.{}", file_exports.module, name);
+                    aliases.insert(&qualified, parameters, &aliased);
+                }
+            }
             let new_funcs: Vec<_> = exports
                 .par_iter()
-                .filter(|export| match export {
+                .filter(|(_, export)| match export {
                     ElmExport::Function { .. } => true,
                     _ => false,
                 })
-                .map(|export| match export {
+                .map(|(file_exports, export)| match export {
                     ElmExport::Function {
                         name,
                         type_signature,
+                        documentation,
+                        fixity,
+                        port,
                     } => {
-                        let a = match type_signature {
-                            Some(typ_sig) => typ_sig.join(" "),
-                            None => String::from(" "),
+                        let (a, resolved, expanded) = match type_signature {
+                            Some(typ_sig) => {
+                                let resolved = file_exports.scope.resolve(typ_sig);
+                                (
+                                    typ_sig.to_string(),
+                                    resolved.to_string(),
+                                    aliases.expand(&resolved).to_string(),
+                                )
+                            }
+                            None => (String::from(" "), String::from(" "), String::from(" ")),
                         };
                         NewFunction {
                             repo_id: repo.id,
                             name: name.as_str(),
                             type_signature: a,
+                            documentation: documentation.as_deref(),
+                            module: file_exports.module.as_str(),
+                            expanded_type_signature: expanded,
+                            resolved_type_signature: resolved,
+                            associativity: fixity.map(|f| f.associativity.to_string()),
+                            precedence: fixity.map(|f| i32::from(f.precedence)),
+                            port: *port,
                         }
                     }
                     _ => panic!(),
@@ -128,9 +204,127 @@ pub fn insert_functions(
     }
 }
 
+/// store the custom types and type aliases exported by the package, replacing any stored before
+pub fn insert_types(
+    cfg: &DbConfig,
+    repo_name: &str,
+    elm_files: &Vec<ElmFile>,
+) -> Result<(), UpdateUrlError> {
+    let db_url = get_db_url(&cfg);
+    let conn = PgConnection::establish(db_url.as_str())?;
+    let mut repos = repositories::table
+        .filter(repositories::name.eq(&repo_name))
+        .limit(1)
+        .load::<Repository>(&conn)?;
+
+    match repos.pop() {
+        Some(repo) => {
+            let repo_id = repo.id;
+            let new_types: Vec<_> = elm_files
+                .iter()
+                .flat_map(|file| {
+                    let module = file.exports.module.as_str();
+                    file.exports
+                        .exports
+                        .iter()
+                        .filter_map(move |export| match export {
+                            ElmExport::Type {
+                                name,
+                                parameters,
+                                definition,
+                                documentation,
+                            } => Some(NewType {
+                                repo_id,
+                                name: name.as_str(),
+                                module,
+                                kind: definition.kind(),
+                                parameters: parameters.as_slice(),
+                                definition: definition.to_string(),
+                                constructors: match definition {
+                                    TypeDefinition::Custom(constructors) => {
+                                        constructors.iter().map(|c| c.to_string()).collect()
+                                    }
+                                    TypeDefinition::Alias(_) | TypeDefinition::Opaque => {
+                                        vec![]
+                                    }
+                                },
+                                documentation: documentation.as_deref(),
+                            }),
+                            _ => None,
+                        })
+                })
+                .collect();
+            conn.transaction(|| -> Result<(), UpdateUrlError> {
+                diesel::delete(types::table.filter(types::repo_id.eq(repo_id))).execute(&conn)?;
+                diesel::insert_into(types::table)
+                    .values(new_types.as_slice())
+                    .execute(&conn)?;
+                Ok(())
+            })
+        }
+        None => {
+            println!("No repository found {}", repo_name);
+            Ok(())
+        }
+    }
+}
+
 pub fn refresh_repo_func_mat_view(cfg: &DbConfig) -> Result<(), Box<Error>> {
     let db_url = get_db_url(&cfg);
     let conn = PgConnection::establish(db_url.as_str())?;
     diesel::sql_query("REFRESH MATERIALIZED VIEW repository_function_mat_view").execute(&conn)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fn_search_backend::get_config;
+    use fn_search_backend_parsers::get_elm_exports;
+
+    static RELATIVE_CFG_FILE: &str = "../config.toml";
+
+    #[test]
+    #[ignore] // needs a database, run with `cargo test -- --ignored`
+    fn insert_types_replaces_stored_types() {
+        let cfg = get_config(RELATIVE_CFG_FILE).expect("error finding configuration file");
+        let repo_name = "fn-search-test/insert-types";
+        update_repo(
+            &cfg.db,
+            repo_name,
+            "https://github.com/fn-search-test/insert-types",
+            "1.0.0",
+        )
+        .expect("error inserting repo");
+        let code = "module Geometry exposing (Point, Shape(..))\n\
+                    type alias Point = { x : Float, y : Float }\n\
+                    type Shape = Circle Point Float";
+        let files = vec![ElmFile {
+            repository: String::new(),
+            path: String::from("src/Geometry.elm"),
+            exports: get_elm_exports(code).expect("error parsing module"),
+        }];
+        insert_types(&cfg.db, repo_name, &files).expect("error inserting types");
+        insert_types(&cfg.db, repo_name, &files).expect("error inserting types again");
+
+        let conn = PgConnection::establish(get_db_url(&cfg.db).as_str())
+            .expect("error establishing connection to db");
+        let names: Vec<String> = types::table
+            .inner_join(repositories::table)
+            .filter(repositories::name.eq(repo_name))
+            .select(types::name)
+            .order(types::name)
+            .load(&conn)
+            .expect("error loading types");
+        let repo_ids = repositories::table
+            .filter(repositories::name.eq(repo_name))
+            .select(repositories::id);
+        diesel::delete(types::table.filter(types::repo_id.eq_any(repo_ids)))
+            .execute(&conn)
+            .expect("error deleting types");
+        diesel::delete(repositories::table.filter(repositories::name.eq(repo_name)))
+            .execute(&conn)
+            .expect("error deleting repo");
+        assert_eq!(names, vec!["Point", "Shape"]);
+    }
+}
@@ -0,0 +1,225 @@
+//! A module for comparing the exports read from the `docs.json` of a package with the exports
+//! the source parser finds, to find declarations the source parser gets wrong.
+
+use fn_search_backend_parsers::{ElmExport, ElmExports, ElmType, TypeDefinition};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// a difference between the exports in `docs.json` and the exports parsed from source code
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportDifference {
+    /// exported according to `docs.json`, but not found in the source code
+    MissingFromSource { module: String, name: String },
+    /// found in the source code, but not exported according to `docs.json`
+    MissingFromDocs { module: String, name: String },
+    /// a function whose type signature, or a type whose definition, in the source code differs
+    /// from `docs.json`
+    DifferentType {
+        module: String,
+        name: String,
+        docs: String,
+        source: String,
+    },
+}
+
+impl fmt::Display for ExportDifference {
+    fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+        match self {
+            ExportDifference::MissingFromSource { module, name } => {
+                write!(f, "{}.{}: missing from source", module, name)
+            }
+            ExportDifference::MissingFromDocs { module, name } => {
+                write!(f, "{}.{}: missing from docs.json", module, name)
+            }
+            ExportDifference::DifferentType {
+                module,
+                name,
+                docs,
+                source,
+            } => write!(
+                f,
+                "{}.{}: docs.json has `{}`, source has `{}`",
+                module, name, docs, source
+            ),
+        }
+    }
+}
+
+/// the exports of every module by module, name and whether they are a type, along with the
+/// type signature of functions and the definition of types with their types resolved
+///
+/// opaque types have no definition, `docs.json` leaves out the constructors the source code has
+fn signatures(modules: &[ElmExports]) -> BTreeMap<(&str, &str, bool), Option<String>> {
+    modules
+        .iter()
+        .flat_map(|module| {
+            module.exports.iter().map(move |export| match export {
+                ElmExport::Function {
+                    name,
+                    type_signature,
+                    ..
+                } => (
+                    (module.module.as_str(), name.as_str(), false),
+                    type_signature
+                        .as_ref()
+                        .map(|sig| module.scope.resolve(sig).to_string()),
+                ),
+                ElmExport::Type {
+                    name, definition, ..
+                } => (
+                    (module.module.as_str(), name.as_str(), true),
+                    match definition {
+                        TypeDefinition::Alias(aliased) => {
+                            Some(module.scope.resolve(aliased).to_string())
+                        }
+                        TypeDefinition::Custom(constructors) => Some(
+                            TypeDefinition::Custom(
+                                constructors
+                                    .iter()
+                                    .map(|constructor| match constructor {
+                                        ElmType::Constructor { name, args } => {
+                                            ElmType::Constructor {
+                                                name: name.clone(),
+                                                args: args
+                                                    .iter()
+                                                    .map(|arg| module.scope.resolve(arg))
+                                                    .collect(),
+                                            }
+                                        }
+                                        _ => constructor.clone(),
+                                    })
+                                    .collect(),
+                            )
+                            .to_string(),
+                        ),
+                        TypeDefinition::Opaque => None,
+                    },
+                ),
+            })
+        })
+        .collect()
+}
+
+/// every difference between the exports of the modules in docs and the modules parsed from
+/// source code, exports missing from the source code or with a different type come first,
+/// then exports missing from docs, each ordered by module and name
+pub fn compare_exports(docs: &[ElmExports], source: &[ElmExports]) -> Vec<ExportDifference> {
+    let docs = signatures(docs);
+    let source = signatures(source);
+    let mut differences = Vec::new();
+    for (&(module, name, is_type), docs_sig) in docs.iter() {
+        let (module_name, export_name) = (module.to_string(), name.to_string());
+        match source.get(&(module, name, is_type)) {
+            None => differences.push(ExportDifference::MissingFromSource {
+                module: module_name,
+                name: export_name,
+            }),
+            // the definition of opaque types can't be compared
+            Some(source_sig) if is_type && (docs_sig.is_none() || source_sig.is_none()) => {}
+            Some(source_sig) if source_sig != docs_sig => {
+                differences.push(ExportDifference::DifferentType {
+                    module: module_name,
+                    name: export_name,
+                    docs: docs_sig.clone().unwrap_or_default(),
+                    source: source_sig.clone().unwrap_or_default(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    for &(module, name, is_type) in source.keys() {
+        if !docs.contains_key(&(module, name, is_type)) {
+            differences.push(ExportDifference::MissingFromDocs {
+                module: module.to_string(),
+                name: name.to_string(),
+            });
+        }
+    }
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fn_search_backend_parsers::{get_docs_exports, get_elm_exports};
+
+    const DOCS: &str = r#"[
+        {
+            "name": "Stack",
+            "comment": "",
+            "unions": [
+                { "name": "Stack", "comment": "", "args": ["a"], "cases": [] },
+                {
+                    "name": "Change",
+                    "comment": "",
+                    "args": ["a"],
+                    "cases": [["Push", ["a"]], ["Pop", []]]
+                }
+            ],
+            "aliases": [
+                { "name": "Split", "comment": "", "args": ["a"], "type": "( a, Stack.Stack a )" }
+            ],
+            "values": [
+                { "name": "empty", "comment": "", "type": "Stack.Stack a" },
+                { "name": "push", "comment": "", "type": "a -> Stack.Stack a -> Stack.Stack a" },
+                { "name": "top", "comment": "", "type": "Stack.Stack a -> Maybe.Maybe a" }
+            ],
+            "binops": []
+        }
+    ]"#;
+
+    fn compare(source: &str) -> Vec<ExportDifference> {
+        let docs = get_docs_exports(DOCS).expect("error reading docs.json");
+        let source = get_elm_exports(source).expect("error parsing module");
+        compare_exports(&docs, &[source])
+    }
+
+    #[test]
+    fn matching_exports_have_no_differences() {
+        let source = "module Stack exposing (Stack, Change(..), Split, empty, push, top)\n\n\
+                      type Stack a = Stack (List a)\n\n\
+                      type Change a = Push a | Pop\n\n\
+                      type alias Split a = ( a, Stack a )\n\n\
+                      empty : Stack a\nempty = Stack []\n\n\
+                      push : a -> Stack a -> Stack a\npush x (Stack xs) = Stack (x :: xs)\n\n\
+                      top : Stack a -> Maybe a\ntop (Stack xs) = List.head xs\n";
+        assert_eq!(compare(source), vec![]);
+    }
+
+    #[test]
+    fn finds_missing_extra_and_different_exports() {
+        let source = "module Stack exposing (Stack, Change(..), Split, empty, push, size)\n\n\
+                      type Stack a = Stack (List a)\n\n\
+                      type Change a = Push a | Pop\n\n\
+                      type alias Split a = ( Stack a, a )\n\n\
+                      empty : Stack a\nempty = Stack []\n\n\
+                      push : Stack a -> a -> Stack a\npush (Stack xs) x = Stack (x :: xs)\n\n\
+                      size : Stack a -> Int\nsize (Stack xs) = List.length xs\n";
+        let module = || String::from("Stack");
+        assert_eq!(
+            compare(source),
+            vec![
+                ExportDifference::DifferentType {
+                    module: module(),
+                    name: String::from("Split"),
+                    docs: String::from("( a, Stack.Stack a )"),
+                    source: String::from("( Stack.Stack a, a )"),
+                },
+                ExportDifference::DifferentType {
+                    module: module(),
+                    name: String::from("push"),
+                    docs: String::from("a -> Stack.Stack a -> Stack.Stack a"),
+                    source: String::from("Stack.Stack a -> a -> Stack.Stack a"),
+                },
+                ExportDifference::MissingFromSource {
+                    module: module(),
+                    name: String::from("top"),
+                },
+                ExportDifference::MissingFromDocs {
+                    module: module(),
+                    name: String::from("size"),
+                },
+            ]
+        );
+    }
+}
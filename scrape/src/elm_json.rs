@@ -0,0 +1,190 @@
+//! A module for reading the `elm.json` of a package, or the `elm-package.json` of packages
+//! written for elm 0.18.
+
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::{error::Error, fmt};
+
+/// the names of package manifests, newest first
+const MANIFEST_FILES: [&str; 2] = ["elm.json", "elm-package.json"];
+
+/// the fields of a package manifest the scraper uses
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageManifest {
+    pub summary: String,
+    pub license: String,
+    /// the modules other packages can import
+    pub exposed_modules: ExposedModules,
+    /// the versions of elm the package works with, e.g. `0.19.0 <= v < 0.20.0`
+    pub elm_version: String,
+    /// version constraints by package name
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+    /// directories containing the source code, only given in `elm-package.json`
+    #[serde(default = "default_source_directories")]
+    pub source_directories: Vec<String>,
+}
+
+fn default_source_directories() -> Vec<String> {
+    vec![String::from("src")]
+}
+
+/// `exposed-modules` is either a list of modules, or lists of modules grouped under headings
+///
+/// ```json
+/// "exposed-modules": {
+///     "Primitives": ["Basics", "String"],
+///     "Collections": ["List", "Dict"]
+/// }
+/// ```
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ExposedModules {
+    List(Vec<String>),
+    Grouped(BTreeMap<String, Vec<String>>),
+}
+
+impl ExposedModules {
+    /// the names of every exposed module
+    pub fn modules(&self) -> Vec<&str> {
+        match self {
+            ExposedModules::List(modules) => modules.iter().map(String::as_str).collect(),
+            ExposedModules::Grouped(groups) => groups
+                .values()
+                .flat_map(|modules| modules.iter().map(String::as_str))
+                .collect(),
+        }
+    }
+}
+
+impl PackageManifest {
+    /// read the manifest of the package at repo_path, returns None if the package has neither
+    /// an `elm.json` nor an `elm-package.json`
+    pub fn read(repo_path: &str) -> Result<Option<PackageManifest>, ManifestError> {
+        let path = match MANIFEST_FILES
+            .iter()
+            .map(|file| Path::new(repo_path).join(file))
+            .find(|path| path.exists())
+        {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let mut json = String::new();
+        File::open(&path)?.read_to_string(&mut json)?;
+        serde_json::from_str(json.as_str())
+            .map(Some)
+            .map_err(|e| ManifestError::InvalidManifest(path, e))
+    }
+
+    /// the files of the exposed modules in the package at repo_path, along with the names of
+    /// exposed modules which couldn't be found in any of the source directories
+    pub fn exposed_files(&self, repo_path: &str) -> (Vec<PathBuf>, Vec<String>) {
+        let mut found = Vec::new();
+        let mut missing = Vec::new();
+        for module in self.exposed_modules.modules() {
+            let file = format!("{}.elm", module.replace('.', "/"));
+            match self
+                .source_directories
+                .iter()
+                .map(|dir| Path::new(repo_path).join(dir).join(&file))
+                .find(|path| path.exists())
+            {
+                Some(path) => found.push(path),
+                None => missing.push(module.to_string()),
+            }
+        }
+        (found, missing)
+    }
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    IoError(io::Error),
+    InvalidManifest(PathBuf, serde_json::Error),
+}
+
+impl Error for ManifestError {}
+
+impl fmt::Display for ManifestError {
+    fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+        match self {
+            ManifestError::IoError(e) => write!(f, "io error while reading manifest: {}", e),
+            ManifestError::InvalidManifest(path, e) => {
+                write!(f, "invalid manifest {}: {}", path.display(), e)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ManifestError {
+    fn from(e: io::Error) -> Self {
+        ManifestError::IoError(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn manifest(exposed_modules: &str) -> PackageManifest {
+        serde_json::from_str(&format!(
+            r#"{{
+                "type": "package",
+                "name": "elm/core",
+                "summary": "Elm's standard libraries",
+                "license": "BSD-3-Clause",
+                "version": "1.0.2",
+                "exposed-modules": {},
+                "elm-version": "0.19.0 <= v < 0.20.0",
+                "dependencies": {{}},
+                "test-dependencies": {{}}
+            }}"#,
+            exposed_modules
+        ))
+        .expect("error reading manifest")
+    }
+
+    #[test]
+    fn reads_a_list_of_exposed_modules() {
+        let manifest = manifest(r#"["Basics", "Dict"]"#);
+        assert_eq!(manifest.exposed_modules.modules(), vec!["Basics", "Dict"]);
+        assert_eq!(manifest.source_directories, vec!["src"]);
+    }
+
+    #[test]
+    fn reads_grouped_exposed_modules() {
+        let manifest = manifest(
+            r#"{
+                "Primitives": ["Basics", "String"],
+                "Collections": ["List", "Dict"]
+            }"#,
+        );
+        // groups are ordered by heading
+        assert_eq!(
+            manifest.exposed_modules.modules(),
+            vec!["List", "Dict", "Basics", "String"]
+        );
+    }
+
+    #[test]
+    fn only_exposed_modules_are_indexed() {
+        let repo = std::env::temp_dir().join("fn_search_elm_json_exposed_files");
+        let _ = fs::remove_dir_all(&repo);
+        fs::create_dir_all(repo.join("src/Json")).expect("error creating source directory");
+        for file in ["src/Json/Decode.elm", "src/Internal.elm"].iter() {
+            fs::write(repo.join(file), "").expect("error writing module");
+        }
+        let repo_path = repo.to_str().expect("temp dir isn't utf-8");
+
+        let manifest = manifest(r#"["Json.Decode", "Json.Encode"]"#);
+        let (found, missing) = manifest.exposed_files(repo_path);
+        assert_eq!(found, vec![repo.join("src/Json/Decode.elm")]);
+        assert_eq!(missing, vec!["Json.Encode"]);
+        fs::remove_dir_all(&repo).expect("error removing temp dir");
+    }
+}
@@ -1,45 +1,58 @@
 //! A module for downloading & finding repository urls for packages
 //! from [package.elm-lang.org](https://package.elm-lang.org).
 //!
-//! For example, if we wanted to iterate over all elm library url's we could
-//! do something like this:
+//! Packages and their released versions are listed by `search.json` and `all-packages`, the
+//! repository of each release is found from its `endpoint.json`. For example, if we wanted to
+//! iterate over the repository of every elm library we could do something like this:
 //!
 //! ```
 //! get_elm_libs()?
 //!     .into_iter()
-//!     .map(|r| find_git_url(&r))
-//!     .for_each(|url| {
-//!         // do something with each url
+//!     .map(|r| r.find_git_repo())
+//!     .for_each(|repo| {
+//!         // do something with each repo
 //!     });
 //! ```
 //!
 
 use crate::chromium_dl::{chrome_dl, ChromeError};
+use crate::elm_json::{ManifestError, PackageManifest};
 use crate::git_repo::{GitError, GitRepo};
 use crate::repo_cache::RepoCacheOptions;
 use fn_search_backend::Config;
-use fn_search_backend_parsers::{get_elm_exports, ElmExports};
+use fn_search_backend_parsers::{get_docs_exports, get_elm_exports, ElmExports, ParseError};
 use glob::{glob, GlobError, PatternError};
 use select::document::Document;
 use select::predicate::{Attr, Class, Predicate};
-use serde::de::IgnoredAny;
+use serde::de::{DeserializeOwned, IgnoredAny};
 use serde_derive::Deserialize;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{error::Error, fmt};
 
 const PACKAGES_BASE_URL: &str = "https://package.elm-lang.org";
 const PACKAGES_SEARCH_URL: &str = "https://package.elm-lang.org/search.json";
+const ALL_PACKAGES_URL: &str = "https://package.elm-lang.org/all-packages";
 
-/// Get a list of elm packages from [package.elm-lang.org](https://package.elm-lang.org)
+/// Get a list of elm packages from [package.elm-lang.org](https://package.elm-lang.org),
+/// along with their released versions
 /// # Error
 /// Returns an error if there is a network failure or the data received by
 /// [package.elm-lang.org](https://package.elm-lang.org) was not in the expected format.
 pub fn get_elm_libs() -> Result<ElmPackageList, Box<Error>> {
-    Ok(serde_json::from_str::<ElmPackageList>(
-        reqwest::get(PACKAGES_SEARCH_URL)?.text()?.as_str(),
-    )?)
+    let mut packages: ElmPackageList = get_json(PACKAGES_SEARCH_URL)?;
+    let mut versions: HashMap<String, Vec<String>> = get_json(ALL_PACKAGES_URL)?;
+    for package in packages.iter_mut() {
+        package.versions = versions.remove(&package.name).unwrap_or_default();
+    }
+    Ok(packages)
+}
+
+/// download and deserialize the json at url
+fn get_json<T: DeserializeOwned>(url: &str) -> Result<T, ElmPackageError> {
+    Ok(serde_json::from_str(reqwest::get(url)?.text()?.as_str())?)
 }
 
 pub type ElmPackageList = Vec<ElmPackage>;
@@ -50,7 +63,16 @@ pub struct ElmPackage {
     pub name: String,
     summary: IgnoredAny,
     license: IgnoredAny,
-    versions: IgnoredAny,
+    /// every released version of the package, from `all-packages`
+    #[serde(skip)]
+    pub versions: Vec<String>,
+}
+
+/// the `endpoint.json` of a release, `url` is the zip file of the tagged release on github,
+/// e.g. `https://github.com/elm/core/zipball/1.0.2/`
+#[derive(Deserialize, Debug)]
+struct Endpoint {
+    url: String,
 }
 
 impl ElmPackage {
@@ -63,14 +85,46 @@ impl ElmPackage {
             .map(|url| String::from(url))
     }
 
-    /// Find the git url for a [ElmPackageMetadataRaw](struct.ElmPackageMetadataRaw.html)
-    pub fn find_git_repo(
+    /// the newest released version of the package
+    pub fn latest_version(&self) -> Option<&str> {
+        self.versions
+            .iter()
+            .max_by_key(|version| {
+                version
+                    .split('.')
+                    .map(|n| n.parse::<u32>().unwrap_or_default())
+                    .collect::<Vec<u32>>()
+            })
+            .map(String::as_str)
+    }
+
+    /// the url of a file published for the latest release of the package
+    fn release_url(&self, file: &str) -> Result<String, ElmPackageError> {
+        let version = self
+            .latest_version()
+            .ok_or_else(|| ElmPackageError::NoReleases(self.name.clone()))?;
+        Ok(format!(
+            "{}/packages/{}/{}/{}",
+            PACKAGES_BASE_URL, self.name, version, file
+        ))
+    }
+
+    /// Find the git repository and tag of the latest release of the package from its
+    /// `endpoint.json`
+    pub fn find_git_repo(&self) -> Result<GitRepo, ElmPackageError> {
+        let endpoint: Endpoint = get_json(self.release_url("endpoint.json")?.as_str())?;
+        Ok(GitRepo::from_zipball_url(endpoint.url.as_str())?)
+    }
+
+    /// Find the git repository of the package from the "Browse Source" link on its page, which
+    /// is rendered by the chromium executable at chromium_bin_path
+    pub fn find_git_repo_with_chrome(
         &self,
+        chromium_bin_path: &str,
         config: &Config,
-        o: &RepoCacheOptions,
     ) -> Result<GitRepo, ElmPackageError> {
-        let url = format!("{}/packages/{}/latest/", PACKAGES_BASE_URL, self.name);
-        let page_text = chrome_dl(url.as_str(), config, o)?;
+        let url = self.package_url();
+        let page_text = chrome_dl(url.as_str(), chromium_bin_path, config)?;
         let document = Document::from(page_text.as_str());
         for n in document.find(Class("pkg-nav-module").and(Attr("href", ()))) {
             if n.text().as_str() == "Browse Source" {
@@ -82,37 +136,88 @@ impl ElmPackage {
         Err(ElmPackageError::CantFindUrl(url.clone()))
     }
 
+    /// the url of the page of the latest version of the package
+    pub fn package_url(&self) -> String {
+        format!("{}/packages/{}/latest/", PACKAGES_BASE_URL, self.name)
+    }
+
+    /// download the `docs.json` of the latest release of the package, and read the exports of
+    /// each of its modules from it
+    pub fn get_docs(&self) -> Result<Vec<ElmExports>, ElmPackageError> {
+        let json = reqwest::get(self.release_url("docs.json")?.as_str())?.text()?;
+        Ok(get_docs_exports(json.as_str())?)
+    }
+
+    /// download the `elm.json` of the latest release of the package
+    pub fn get_released_manifest(&self) -> Result<PackageManifest, ElmPackageError> {
+        get_json(self.release_url("elm.json")?.as_str())
+    }
+
+    /// the manifest of the package in the cache, None if it has no `elm.json`
+    pub fn manifest(
+        &self,
+        o: &RepoCacheOptions,
+    ) -> Result<Option<PackageManifest>, ElmPackageError> {
+        let path = self.get_repo_path(&o)?;
+        Ok(PackageManifest::read(path.as_str())?)
+    }
+
     // get the exports of a elm package
-    // returns an error, or a vector of results which are either ElmFiles, or the path to a file that failed to parse
+    // only the modules exposed in the manifest of the package are parsed, or every module in src
+    // for packages without a manifest
+    // returns an error, or a vector of results which are either ElmFiles, or the reason a file failed to parse
     pub fn get_exports(
         &self,
         o: &RepoCacheOptions,
-    ) -> Result<Vec<Result<ElmFile, String>>, ElmPackageError> {
+    ) -> Result<Vec<Result<ElmFile, ParseFailure>>, ElmPackageError> {
         let path = self.get_repo_path(&o)?;
-        let f_iter = glob(format!("{}/src/**/*.elm", path).as_str())?;
-        f_iter
-            .map(|res| -> Result<Result<ElmFile, String>, ElmPackageError> {
-                let res = res?;
-                let path = res.as_path();
-                let mut file = File::open(path)?;
-                let mut elm_code = String::new();
-                file.read_to_string(&mut elm_code)?;
-                match get_elm_exports(elm_code.as_str()) {
-                    Ok(e) => Ok(Ok(ElmFile {
-                        repository: path
-                            .to_str()
-                            .expect("cache path was not convertible into a string")
-                            .to_string(),
-                        path: path.to_str().unwrap_or_default().to_string(),
-                        exports: e,
-                    })),
-                    Err(_) => Ok(Err(path.to_str().unwrap_or_default().to_string())),
+        let files = match self.manifest(o)? {
+            Some(manifest) => {
+                let (files, missing) = manifest.exposed_files(path.as_str());
+                for module in missing.iter() {
+                    eprintln!("{}: can't find exposed module {}", self.name, module);
                 }
-            })
+                files
+            }
+            None => glob(format!("{}/src/**/*.elm", path).as_str())?
+                .collect::<Result<Vec<PathBuf>, GlobError>>()?,
+        };
+        files
+            .iter()
+            .map(|file_path| parse_file(file_path, path.as_str()))
             .collect()
     }
 }
 
+/// parse the elm file at file_path, in the package at repo_path
+fn parse_file(
+    file_path: &Path,
+    repo_path: &str,
+) -> Result<Result<ElmFile, ParseFailure>, ElmPackageError> {
+    let mut file = File::open(file_path)?;
+    let mut elm_code = String::new();
+    file.read_to_string(&mut elm_code)?;
+    match get_elm_exports(elm_code.as_str()) {
+        Ok(e) => Ok(Ok(ElmFile {
+            repository: file_path
+                .to_str()
+                .expect("cache path was not convertible into a string")
+                .to_string(),
+            path: file_path.to_str().unwrap_or_default().to_string(),
+            exports: e,
+        })),
+        Err(error) => Ok(Err(ParseFailure {
+            path: file_path
+                .strip_prefix(repo_path)
+                .unwrap_or(file_path)
+                .to_str()
+                .unwrap_or_default()
+                .to_string(),
+            error,
+        })),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ElmFile {
     pub repository: String,
@@ -120,6 +225,40 @@ pub struct ElmFile {
     pub exports: ElmExports,
 }
 
+impl ElmFile {
+    /// the declarations in the file which couldn't be parsed, and were skipped
+    pub fn parse_failures(&self, repo_path: &str) -> Vec<ParseFailure> {
+        let path = Path::new(self.path.as_str());
+        let path = path
+            .strip_prefix(repo_path)
+            .unwrap_or(path)
+            .to_str()
+            .unwrap_or_default();
+        self.exports
+            .errors
+            .iter()
+            .map(|error| ParseFailure {
+                path: path.to_string(),
+                error: error.clone(),
+            })
+            .collect()
+    }
+}
+
+/// an elm file, or a declaration in it, which couldn't be parsed
+#[derive(Debug, Clone)]
+pub struct ParseFailure {
+    /// the path of the file, relative to the package
+    pub path: String,
+    pub error: ParseError,
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> fmt::Result {
+        write!(f, "{}:{}", self.path, self.error)
+    }
+}
+
 #[derive(Debug)]
 pub enum ElmPackageError {
     GitError(GitError),
@@ -129,6 +268,10 @@ pub enum ElmPackageError {
     GlobError(GlobError),
     GlobPatternError(PatternError),
     IoError(io::Error),
+    ManifestError(ManifestError),
+    RequestError(reqwest::Error),
+    InvalidJson(serde_json::Error),
+    NoReleases(String),
 }
 
 impl Error for ElmPackageError {}
@@ -143,6 +286,14 @@ impl fmt::Display for ElmPackageError {
             ElmPackageError::GlobError(e) => write!(f, "error while globbing: {}", e),
             ElmPackageError::GlobPatternError(e) => write!(f, "invalid glob pattern: {}", e),
             ElmPackageError::IoError(e) => write!(f, "io error while getting exports: {}", e),
+            ElmPackageError::ManifestError(e) => write!(f, "{}", e),
+            ElmPackageError::RequestError(e) => {
+                write!(f, "error downloading from package.elm-lang.org: {}", e)
+            }
+            ElmPackageError::InvalidJson(e) => {
+                write!(f, "invalid json from package.elm-lang.org: {}", e)
+            }
+            ElmPackageError::NoReleases(p) => write!(f, "no released versions of {}", p),
         }
     }
 }
@@ -176,3 +327,51 @@ impl From<io::Error> for ElmPackageError {
         ElmPackageError::IoError(e)
     }
 }
+
+impl From<ManifestError> for ElmPackageError {
+    fn from(e: ManifestError) -> Self {
+        ElmPackageError::ManifestError(e)
+    }
+}
+
+impl From<reqwest::Error> for ElmPackageError {
+    fn from(e: reqwest::Error) -> Self {
+        ElmPackageError::RequestError(e)
+    }
+}
+
+impl From<serde_json::Error> for ElmPackageError {
+    fn from(e: serde_json::Error) -> Self {
+        ElmPackageError::InvalidJson(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(versions: &[&str]) -> ElmPackage {
+        let mut package: ElmPackage =
+            serde_json::from_str(r#"{ "name": "elm/core", "summary": "", "license": "" }"#)
+                .expect("error reading package");
+        package.versions = versions.iter().map(|v| v.to_string()).collect();
+        package
+    }
+
+    #[test]
+    fn latest_version_compares_version_numbers() {
+        assert_eq!(
+            package(&["1.10.0", "1.0.0", "1.9.0"]).latest_version(),
+            Some("1.10.0")
+        );
+        assert_eq!(
+            package(&["1.9.0", "1.10.0"]).latest_version(),
+            Some("1.10.0")
+        );
+        assert_eq!(
+            package(&["2.0.0", "1.10.3"]).latest_version(),
+            Some("2.0.0")
+        );
+        assert_eq!(package(&[]).latest_version(), None);
+    }
+}
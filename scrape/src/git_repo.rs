@@ -32,6 +32,28 @@ impl GitRepo {
         Err(GitError::ParseError(url.to_string()))
     }
 
+    /// the repository and tag of a release from the url of its zip file on github,
+    /// `https://github.com/elm/core/zipball/1.0.2/`
+    pub fn from_zipball_url(url: &str) -> Result<Self, GitError> {
+        lazy_static! {
+            static ref ZIPBALL_REGEX: Regex = Regex::new(
+                r"^(?P<url>https://github\.com/[^/]+/[^/]+)/zipball/(?P<version>[\.\d]+)/?$"
+            )
+            .expect("failed to build zipball regex");
+        }
+        if let Some(captures) = ZIPBALL_REGEX.captures(url) {
+            if let Some(url) = captures.name("url") {
+                if let Some(version) = captures.name("version") {
+                    return Ok(GitRepo {
+                        url: url.as_str().to_string(),
+                        version: version.as_str().to_string(),
+                    });
+                }
+            }
+        }
+        Err(GitError::ParseError(url.to_string()))
+    }
+
     pub fn clone_repo(
         &self,
         repo_path: &str,
@@ -99,3 +121,28 @@ impl From<ExecError> for GitError {
         GitError::ProcessError(e)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_repository_and_tag_from_zipball_url() {
+        let repo = GitRepo::from_zipball_url("https://github.com/elm/core/zipball/1.0.2/")
+            .expect("error parsing zipball url");
+        assert_eq!(repo.url, "https://github.com/elm/core");
+        assert_eq!(repo.version, "1.0.2");
+        let repo =
+            GitRepo::from_zipball_url("https://github.com/elm-community/list-extra/zipball/8.2.0")
+                .expect("error parsing zipball url");
+        assert_eq!(repo.url, "https://github.com/elm-community/list-extra");
+        assert_eq!(repo.version, "8.2.0");
+    }
+
+    #[test]
+    fn rejects_zipball_urls_outside_github() {
+        assert!(GitRepo::from_zipball_url("https://gitlab.com/elm/core/zipball/1.0.2/").is_err());
+        assert!(GitRepo::from_zipball_url("http://example.com/zipball/1.0.2/").is_err());
+        assert!(GitRepo::from_zipball_url("https://github.com/elm/core/tree/1.0.2").is_err());
+    }
+}
@@ -5,24 +5,37 @@
 //!
 //! * Download the list of packages on [packages.elm-lang.org](https://packages.elm-lang.org)
 //! * Iterate over each repository in parallel
+//!   * Find the repository and tag of the latest release from its `endpoint.json`, or with
+//!     chromium if `--chrome` is given and the registry doesn't know
 //!   * Check if the repository already is cached
 //!     * If yes, spawn a subprocess and run git pull to update the repository
 //!     * If no, spawn a subprocess and run git clone to download the repository
-//!   * Run a Elm parser on the source code to find all exported functions/variables/etc...
+//!   * Read the `elm.json` of the package for its metadata and exposed modules
+//!   * Run a Elm parser on the exposed modules to find all exported functions/variables/etc...
 //!   * Insert exported functions and types into the database
+//!
+//! The `docs` subcommand indexes packages from the `docs.json` published for each of them
+//! instead, and `docs --compare` prints where it disagrees with the source parser
 
 pub mod chromium_dl;
 pub mod db_queries;
+pub mod docs_comparison;
+pub mod elm_json;
 pub mod elm_package;
 pub mod git_repo;
 pub mod repo_cache;
 mod subprocess;
 
-use crate::db_queries::{insert_functions, refresh_repo_func_mat_view};
-use crate::elm_package::{ElmFile, ElmPackage, ElmPackageError};
+use crate::db_queries::{
+    insert_functions, insert_types, refresh_repo_func_mat_view, update_package_metadata,
+    update_repo,
+};
+use crate::docs_comparison::{compare_exports, ExportDifference};
+use crate::elm_package::{ElmFile, ElmPackage, ElmPackageError, ParseFailure};
 use crate::repo_cache::{sync_repo, RepoCacheOptions, SyncRepoError, SyncResult};
 use clap::{clap_app, crate_authors, crate_description, crate_version, ArgMatches};
 use fn_search_backend::{get_config, Config};
+use fn_search_backend_parsers::ElmExports;
 use rayon::prelude::*;
 use std::collections::HashMap;
 use std::error::Error;
@@ -42,7 +55,8 @@ fn sync(cfg: &Config, cache_config: &RepoCacheOptions) -> Result<(), Box<Error>>
             }
             Err(e) => {
                 match &e {
-                    // chrome doesn't finish downloading the page sometimes, try again
+                    // when falling back to chrome, it doesn't finish downloading the page
+                    // sometimes, try again
                     SyncRepoError::ElmPackageError(ElmPackageError::CantFindUrl(_)) => Some(r.0),
                     _ => {
                         eprintln!("error syncing repo {}: {}", r.0.name, e);
@@ -83,7 +97,8 @@ fn parse(cfg: &Config, cache_config: &RepoCacheOptions) -> Result<(), Box<Error>
         .collect();
 
     println!("reducing exports...");
-    // convert the exports into a more usable format
+    // convert the exports into a more usable format, keeping track of what couldn't be parsed
+    let mut parse_failures: HashMap<String, Vec<ParseFailure>> = HashMap::new();
     let reduced_exports: Vec<_> = exports
         .into_iter()
         .fold(repo_exports, |mut repo_exports, res| match res.1 {
@@ -91,23 +106,28 @@ fn parse(cfg: &Config, cache_config: &RepoCacheOptions) -> Result<(), Box<Error>
                 for file_res in file_res_vec {
                     match file_res {
                         Ok(elm_file) => match res.0.get_repo_path(cache_config) {
-                            Ok(repo_path) => match repo_exports.get(&repo_path) {
-                                Some(elm_files) => {
-                                    let mut new_elm_files = elm_files.clone();
-                                    new_elm_files.push(elm_file);
-                                    repo_exports
-                                        .insert(res.0.name.to_string(), new_elm_files.to_vec());
-                                }
-                                None => {
-                                    repo_exports.insert(res.0.name.to_string(), vec![elm_file]);
+                            Ok(repo_path) => {
+                                let failures = elm_file.parse_failures(&repo_path);
+                                if !failures.is_empty() {
+                                    parse_failures
+                                        .entry(res.0.name.to_string())
+                                        .or_default()
+                                        .extend(failures);
                                 }
-                            },
+                                repo_exports
+                                    .entry(res.0.name.to_string())
+                                    .or_default()
+                                    .push(elm_file);
+                            }
                             Err(e) => {
                                 eprintln!("error while finding repository path: {}", e);
                             }
                         },
-                        Err(e) => {
-                            eprintln!("error while parsing file: {}", e);
+                        Err(failure) => {
+                            parse_failures
+                                .entry(res.0.name.to_string())
+                                .or_default()
+                                .push(failure);
                         }
                     }
                 }
@@ -121,13 +141,32 @@ fn parse(cfg: &Config, cache_config: &RepoCacheOptions) -> Result<(), Box<Error>
         .into_iter()
         .collect();
 
-    println!("inserting functions into db...");
-    // insert the exported functions into the database
+    report_parse_failures(&parse_failures);
+
+    println!("inserting package metadata into db...");
+    elm_libs
+        .par_iter()
+        .for_each(|package| match package.manifest(cache_config) {
+            Ok(Some(manifest)) => {
+                if let Err(e) = update_package_metadata(&cfg.db, &package.name, &manifest) {
+                    eprintln!("error while inserting package metadata: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("error while reading manifest of {}: {}", package.name, e),
+        });
+
+    println!("inserting functions and types into db...");
+    // insert the exported functions and types into the database
     reduced_exports.into_par_iter().for_each(|(name, exports)| {
         match insert_functions(&cfg.db, &name, &exports) {
             Ok(_) => {}
             Err(e) => eprintln!("error while inserting functions: {}", e),
         }
+        match insert_types(&cfg.db, &name, &exports) {
+            Ok(_) => {}
+            Err(e) => eprintln!("error while inserting types: {}", e),
+        }
     });
 
     println!("refreshing materialized views...");
@@ -135,13 +174,154 @@ fn parse(cfg: &Config, cache_config: &RepoCacheOptions) -> Result<(), Box<Error>
     Ok(())
 }
 
+/// index packages using the docs.json published for each of them, without cloning their
+/// repositories or parsing their source code
+fn docs(cfg: &Config) -> Result<(), Box<Error>> {
+    let elm_libs = elm_package::get_elm_libs()?;
+
+    println!("downloading docs.json of each package...");
+    let docs: Vec<(&ElmPackage, Vec<ElmExports>)> = elm_libs
+        .par_iter()
+        .filter_map(|package| match package.get_docs() {
+            Ok(modules) => Some((package, modules)),
+            Err(e) => {
+                eprintln!("error getting docs of {}: {}", package.name, e);
+                None
+            }
+        })
+        .collect();
+
+    // types in docs.json which couldn't be parsed, by the module they are in
+    let mut parse_failures: HashMap<String, Vec<ParseFailure>> = HashMap::new();
+    for (package, modules) in docs.iter() {
+        for module in modules.iter() {
+            parse_failures
+                .entry(package.name.to_string())
+                .or_default()
+                .extend(module.errors.iter().map(|error| ParseFailure {
+                    path: module.module.clone(),
+                    error: error.clone(),
+                }));
+        }
+    }
+    parse_failures.retain(|_, failures| !failures.is_empty());
+    report_parse_failures(&parse_failures);
+
+    println!("inserting functions and types into db...");
+    docs.into_par_iter().for_each(|(package, modules)| {
+        // the url of the repository is stored, as it is when syncing the package
+        let git_repo = match package.find_git_repo() {
+            Ok(git_repo) => git_repo,
+            Err(e) => {
+                eprintln!("error finding repository of {}: {}", package.name, e);
+                return;
+            }
+        };
+        if let Err(e) = update_repo(&cfg.db, &package.name, &git_repo.url, &git_repo.version) {
+            eprintln!("error while inserting repository {}: {}", package.name, e);
+            return;
+        }
+        match package.get_released_manifest() {
+            Ok(manifest) => {
+                if let Err(e) = update_package_metadata(&cfg.db, &package.name, &manifest) {
+                    eprintln!("error while inserting package metadata: {}", e);
+                }
+            }
+            Err(e) => eprintln!("error getting elm.json of {}: {}", package.name, e),
+        }
+        let elm_files: Vec<ElmFile> = modules
+            .into_iter()
+            .map(|exports| ElmFile {
+                repository: git_repo.url.clone(),
+                path: exports.module.clone(),
+                exports,
+            })
+            .collect();
+        match insert_functions(&cfg.db, &package.name, &elm_files) {
+            Ok(_) => {}
+            Err(e) => eprintln!("error while inserting functions: {}", e),
+        }
+        match insert_types(&cfg.db, &package.name, &elm_files) {
+            Ok(_) => {}
+            Err(e) => eprintln!("error while inserting types: {}", e),
+        }
+    });
+
+    println!("refreshing materialized views...");
+    refresh_repo_func_mat_view(&cfg.db)?;
+    Ok(())
+}
+
+/// prints the differences between the exports in the docs.json of each package and the
+/// exports parsed from its cached repository
+fn compare_docs(cache_config: &RepoCacheOptions) -> Result<(), Box<Error>> {
+    let elm_libs = elm_package::get_elm_libs()?;
+
+    println!("comparing docs.json with parsed source code...");
+    let mut differences: Vec<(&str, Vec<ExportDifference>)> = elm_libs
+        .par_iter()
+        .filter_map(|package| {
+            let docs = match package.get_docs() {
+                Ok(docs) => docs,
+                Err(e) => {
+                    eprintln!("error getting docs of {}: {}", package.name, e);
+                    return None;
+                }
+            };
+            let source: Vec<ElmExports> = match package.get_exports(cache_config) {
+                Ok(files) => files
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .map(|file| file.exports)
+                    .collect(),
+                Err(e) => {
+                    eprintln!("error while trying to parse {}: {}", package.name, e);
+                    return None;
+                }
+            };
+            Some((package.name.as_str(), compare_exports(&docs, &source)))
+        })
+        .filter(|(_, differences)| !differences.is_empty())
+        .collect();
+    differences.sort_by(|a, b| a.0.cmp(b.0));
+    for (package, differences) in differences.iter() {
+        println!("{}: {} differences", package, differences.len());
+        for difference in differences.iter() {
+            println!("{}", difference);
+        }
+    }
+    println!(
+        "{} differences in {} packages",
+        differences.iter().map(|(_, d)| d.len()).sum::<usize>(),
+        differences.len()
+    );
+    Ok(())
+}
+
+/// prints the files and declarations of each package which couldn't be parsed
+fn report_parse_failures(parse_failures: &HashMap<String, Vec<ParseFailure>>) {
+    let mut packages: Vec<_> = parse_failures.iter().collect();
+    packages.sort_by(|a, b| a.0.cmp(b.0));
+    for (package, failures) in packages.iter() {
+        eprintln!("{}: {} parse failures", package, failures.len());
+        for failure in failures.iter() {
+            eprintln!("{}", failure);
+        }
+    }
+    eprintln!(
+        "{} parse failures in {} packages",
+        parse_failures.values().map(Vec::len).sum::<usize>(),
+        parse_failures.len()
+    );
+}
+
 fn main() -> Result<(), Box<Error>> {
     let matches: ArgMatches = clap_app!(fn_search_backend_scrape =>
         (version: crate_version!())
         (author: crate_authors!())
         (about: crate_description!())
         (@arg CACHE_DIR: -d --("cache-dir") +takes_value +required "directory for repositories to be cached in")
-        (@arg CHROME: -h --chrome +takes_value +required default_value("chromium") "google chrome or chromium executable")
+        (@arg CHROME: -h --chrome +takes_value "google chrome or chromium executable, used to find repositories the package registry can't")
         (@arg GIT: -g --git +takes_value +required default_value("git") "git executable")
         (@arg CONFIG: -c --config +takes_value +required "configuration file")
         (@subcommand sync =>
@@ -150,24 +330,34 @@ fn main() -> Result<(), Box<Error>> {
         (@subcommand parse =>
             (about: "parse elm files")
         )
+        (@subcommand docs =>
+            (about: "index packages from their docs.json, without cloning their repositories")
+            (@arg COMPARE: --compare "compare docs.json with the exports parsed from the cached repositories instead")
+        )
     ).get_matches();
 
     let cache_dir = matches
         .value_of("CACHE_DIR")
         .expect("error, no cache directory specified");
-    let chrome = matches.value_of("CHROME").unwrap();
+    let chrome = matches.value_of("CHROME");
     let git = matches.value_of("GIT").unwrap();
     let config = matches.value_of("CONFIG").unwrap();
     let config = get_config(&config).map_err(|e| e as Box<Error>)?;
     let cache_config = RepoCacheOptions {
         cache_path: String::from(cache_dir),
-        chromium_bin_path: chrome.to_string(),
+        chromium_bin_path: chrome.map(String::from),
         git_bin_path: git.to_string(),
     };
     if let Some(_) = matches.subcommand_matches("sync") {
         sync(&config, &cache_config)?;
     } else if let Some(_) = matches.subcommand_matches("parse") {
         parse(&config, &cache_config)?;
+    } else if let Some(docs_matches) = matches.subcommand_matches("docs") {
+        if docs_matches.is_present("COMPARE") {
+            compare_docs(&cache_config)?;
+        } else {
+            docs(&config)?;
+        }
     } else {
         eprintln!("usage: fn_search_backend_scrape --help");
     }
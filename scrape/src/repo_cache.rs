@@ -11,7 +11,8 @@ use std::{error::Error, fmt};
 pub struct RepoCacheOptions {
     /// root path for cache
     pub cache_path: String,
-    pub chromium_bin_path: String,
+    /// chromium is only used to find repositories the package registry can't tell us about
+    pub chromium_bin_path: Option<String>,
     pub git_bin_path: String,
 }
 
@@ -41,7 +42,17 @@ pub fn sync_repo(
     config: &Config,
 ) -> Result<SyncResult, SyncRepoError> {
     let repo_path = m.get_repo_path(o)?;
-    let git_repo = m.find_git_repo(config, o)?;
+    let git_repo = match (m.find_git_repo(), o.chromium_bin_path.as_ref()) {
+        (Ok(git_repo), _) => git_repo,
+        (Err(e), Some(chromium_bin_path)) => {
+            eprintln!(
+                "error finding repository of {} from the registry, trying chrome: {}",
+                m.name, e
+            );
+            m.find_git_repo_with_chrome(chromium_bin_path, config)?
+        }
+        (Err(e), None) => return Err(e.into()),
+    };
     update_repo(
         &config.db,
         m.name.as_str(),
@@ -1,4 +1,4 @@
-use crate::collections::FnCache;
+use crate::collections::{FnCache, NameIndex};
 use actix_web::*;
 use fn_search_backend_db::diesel::pg::PgConnection;
 use parking_lot::RwLock;
@@ -13,13 +13,19 @@ pub type PoolConnRes = Result<PoolConn, R2D2Error>;
 pub struct AppState {
     pool: Pool<ConnectionManager<PgConnection>>,
     cache: RwLock<Arc<FnCache>>,
+    names: RwLock<Arc<NameIndex>>,
 }
 
 impl AppState {
-    pub fn new(pool: Pool<ConnectionManager<PgConnection>>, cache: Arc<FnCache>) -> Self {
+    pub fn new(
+        pool: Pool<ConnectionManager<PgConnection>>,
+        cache: Arc<FnCache>,
+        names: Arc<NameIndex>,
+    ) -> Self {
         AppState {
             pool,
             cache: RwLock::new(cache),
+            names: RwLock::new(names),
         }
     }
 
@@ -38,4 +44,12 @@ impl AppState {
     pub fn update_fn_cache(&self, c: FnCache) {
         *self.cache.write() = Arc::new(c);
     }
+
+    pub fn get_name_index(&self) -> Arc<NameIndex> {
+        self.names.read().clone()
+    }
+
+    pub fn update_name_index(&self, n: NameIndex) {
+        *self.names.write() = Arc::new(n);
+    }
 }
@@ -1,3 +1,5 @@
 mod fn_cache;
+mod name_index;
 
-pub use crate::collections::fn_cache::FnCache;
+pub use crate::collections::fn_cache::{FnCache, Page};
+pub use crate::collections::name_index::{NameIndex, NameMatch};
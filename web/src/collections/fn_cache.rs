@@ -1,20 +1,67 @@
+use crate::matching::{
+    has_records, match_permuted, match_record_fields, match_types, type_distance, MAX_DISTANCE,
+};
 use fn_search_backend_db::models::Function;
+use fn_search_backend_parsers::{normalize_prefix, parse_type_signature, ElmType};
 use radix_trie::{Trie, TrieCommon};
 use std::iter::FromIterator;
 
+/// parses and normalizes a type signature, returning the normalized type along with the key
+/// used for it in the cache, type variables are renamed in order of appearance and
+/// whitespace/parentheses are normalized
+///
+/// signatures which can't be parsed only have their whitespace collapsed
+fn normalize_type_signature(sig: &str) -> (Option<ElmType>, String) {
+    match parse_type_signature(sig) {
+        Some(t) => {
+            let t = t.normalize();
+            let key = t.to_string();
+            (Some(t), key)
+        }
+        None => (
+            None,
+            sig.split_whitespace().collect::<Vec<&str>>().join(" "),
+        ),
+    }
+}
+
+/// a page of search results, along with the number of results across all pages
+#[derive(Debug, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    pub fn map<U, F: FnMut(T) -> U>(self, f: F) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+        }
+    }
+}
+
 pub struct FnCache {
     trie: Trie<String, Vec<i64>>,
+    /// every distinct signature in the trie which could be parsed
+    types: Vec<(String, ElmType)>,
 }
 
 impl FnCache {
     fn new() -> Self {
-        FnCache { trie: Trie::new() }
+        FnCache {
+            trie: Trie::new(),
+            types: Vec::new(),
+        }
     }
 
     /// returns at most num function ids with signature sig, starting at index starting_index
+    ///
+    /// sig is normalized first, so `x -> List x` finds functions stored as `a -> List a`
     pub fn search(&self, sig: &str, num: usize, starting_index: Option<usize>) -> Option<&[i64]> {
-        if let Some(cache) = self.trie.get(sig) {
-            let start = if let Some(s) = starting_index { s } else { 0 };
+        let (_, sig) = normalize_type_signature(sig);
+        if let Some(cache) = self.trie.get(sig.as_str()) {
+            let start = starting_index.unwrap_or(0);
             let len = cache.len();
             if start >= len {
                 return None;
@@ -26,11 +73,121 @@ impl FnCache {
         }
     }
 
-    /// returns at most num suggested type signature ids for completing sig
-    pub fn suggest(&self, sig: &str, num: usize) -> Option<Vec<&str>> {
-        if let Some(t) = self.trie.get_raw_descendant(sig) {
+    /// returns the number of functions with signature sig
+    pub fn count(&self, sig: &str) -> usize {
+        let (_, sig) = normalize_type_signature(sig);
+        self.trie.get(sig.as_str()).map_or(0, |cache| cache.len())
+    }
+
+    /// returns at most num function ids whose signature unifies with sig, starting at index
+    /// starting_index
+    ///
+    /// exact matches come first, followed by signatures more general than sig, signatures more
+    /// specific than sig, then signatures which can be specialized to a common type with sig
+    pub fn search_unifiable(
+        &self,
+        sig: &str,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Option<Page<i64>> {
+        let query = parse_type_signature(sig)?.normalize();
+        self.search_ranked(|t| match_types(&query, t), num, starting_index)
+            .map(|page| page.map(|(id, _)| id))
+    }
+
+    /// like [search_unifiable](#method.search_unifiable), but the arguments of sig may be given
+    /// in any order, signatures needing fewer arguments to be reordered come first
+    pub fn search_any_order(
+        &self,
+        sig: &str,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Option<Page<i64>> {
+        let query = parse_type_signature(sig)?.normalize();
+        self.search_ranked(|t| match_permuted(&query, t), num, starting_index)
+            .map(|page| page.map(|(id, _)| id))
+    }
+
+    /// returns at most num function ids whose signature is sig, except that their records may
+    /// have fields the records in sig don't mention, starting at index starting_index
+    ///
+    /// signatures with the fewest additional fields come first, returns None if sig has no
+    /// records
+    pub fn search_record_fields(
+        &self,
+        sig: &str,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Option<Page<i64>> {
+        let query = parse_type_signature(sig)?.normalize();
+        if !has_records(&query) {
+            return None;
+        }
+        self.search_ranked(|t| match_record_fields(&query, t), num, starting_index)
+            .map(|page| page.map(|(id, _)| id))
+    }
+
+    /// returns at most num function ids along with the distance of their signature from sig,
+    /// starting at index starting_index, closest signatures first
+    pub fn search_approximate(
+        &self,
+        sig: &str,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Option<Page<(i64, u32)>> {
+        let query = parse_type_signature(sig)?.normalize();
+        self.search_ranked(
+            |t| Some(type_distance(&query, t)).filter(|d| *d <= MAX_DISTANCE),
+            num,
+            starting_index,
+        )
+    }
+
+    /// returns at most num function ids along with the rank of their signature, starting at
+    /// index starting_index, ordered by rank, signatures without a rank are left out
+    ///
+    /// returns None if no signature has a rank
+    fn search_ranked<R, F>(
+        &self,
+        rank: F,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Option<Page<(i64, R)>>
+    where
+        R: Ord + Copy,
+        F: Fn(&ElmType) -> Option<R>,
+    {
+        let mut matches: Vec<(R, &str, &Vec<i64>)> = self
+            .types
+            .iter()
+            .filter_map(|(key, t)| rank(t).map(|r| (r, key.as_str())))
+            .filter_map(|(r, key)| self.trie.get(key).map(|ids| (r, key, ids)))
+            .collect();
+        if matches.is_empty() {
+            return None;
+        }
+        matches.sort_by(|(r1, k1, _), (r2, k2, _)| r1.cmp(r2).then(k1.cmp(k2)));
+        let total = matches.iter().map(|(_, _, ids)| ids.len()).sum();
+        let items = matches
+            .into_iter()
+            .flat_map(|(r, _, ids)| ids.iter().map(move |id| (*id, r)))
+            .skip(starting_index.unwrap_or(0))
+            .take(num)
+            .collect();
+        Some(Page { items, total })
+    }
+
+    /// returns at most num suggested type signatures for completing sig,
+    /// starting at index starting_index, sig is normalized like the signatures in the cache
+    pub fn suggest(
+        &self,
+        sig: &str,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Option<Vec<&str>> {
+        if let Some(t) = self.trie.get_raw_descendant(&normalize_prefix(sig)) {
             let mut res = Vec::new();
-            for k in (&t).keys().take(num) {
+            for k in (&t).keys().skip(starting_index.unwrap_or(0)).take(num) {
                 res.push(k.as_str());
             }
             if res.is_empty() {
@@ -43,16 +200,58 @@ impl FnCache {
         }
     }
 
-    // ASSUME EACH FUNCTION IS ONLY INSERTED ONCE!!!
+    /// returns the number of type signatures which complete sig
+    pub fn suggest_count(&self, sig: &str) -> usize {
+        self.trie
+            .get_raw_descendant(&normalize_prefix(sig))
+            .map_or(0, |t| (&t).keys().count())
+    }
+
+    // ASSUME EACH NORMALIZED SIGNATURE OF A FUNCTION IS ONLY INSERTED ONCE!!!
+    // insert_function dedupes the written, resolved and alias expanded signatures of a function
     fn insert(&mut self, type_signature: &str, func_id: i64) {
+        let (t, key) = normalize_type_signature(type_signature);
+        self.insert_normalized(t, key, func_id);
+    }
+
+    fn insert_normalized(&mut self, t: Option<ElmType>, key: String, func_id: i64) {
+        if let Some(t) = t {
+            if self.trie.get(key.as_str()).is_none() {
+                self.types.push((key.clone(), t));
+            }
+        }
         self.trie.map_with_default(
-            type_signature.to_string(),
+            key,
             |cache| {
                 cache.push(func_id);
             },
             [func_id].to_vec(),
         );
     }
+
+    /// inserts each distinct signature of f, as written, with type names resolved and with
+    /// type aliases expanded, signatures are compared after normalization so e.g. `a -> a`
+    /// and `b -> b` are only inserted once
+    fn insert_function(&mut self, f: &Function) {
+        let mut sigs = vec![normalize_type_signature(f.type_signature.as_str())];
+        for sig in [
+            f.resolved_type_signature.as_str(),
+            f.expanded_type_signature.as_str(),
+        ]
+        .iter()
+        {
+            if sig.is_empty() {
+                continue;
+            }
+            let (t, key) = normalize_type_signature(sig);
+            if !sigs.iter().any(|(_, k)| *k == key) {
+                sigs.push((t, key));
+            }
+        }
+        for (t, key) in sigs {
+            self.insert_normalized(t, key, f.id);
+        }
+    }
 }
 
 impl FromIterator<(String, i64)> for FnCache {
@@ -79,7 +278,7 @@ impl FromIterator<Function> for FnCache {
     fn from_iter<T: IntoIterator<Item = Function>>(fns: T) -> Self {
         let mut c = FnCache::new();
         for f in fns {
-            c.insert(f.type_signature.as_str(), f.id);
+            c.insert_function(&f);
         }
         c
     }
@@ -89,7 +288,7 @@ impl<'a> FromIterator<&'a Function> for FnCache {
     fn from_iter<T: IntoIterator<Item = &'a Function>>(fns: T) -> Self {
         let mut c = FnCache::new();
         for f in fns {
-            c.insert(f.type_signature.as_str(), f.id);
+            c.insert_function(f);
         }
         c
     }
@@ -0,0 +1,137 @@
+use crate::collections::Page;
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::iter::FromIterator;
+use std::ops::Bound;
+
+/// How a function name matches the name being searched for.
+///
+/// Variants are ordered from most to least relevant.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum NameMatch {
+    /// the names are the same
+    Exact,
+    /// the function name starts with the query
+    Prefix,
+    /// the function name contains the query, ignoring case
+    #[default]
+    Substring,
+}
+
+/// find how name matches query, query_lower is query converted to lowercase
+fn match_name(query: &str, query_lower: &str, name: &str) -> Option<NameMatch> {
+    if name == query {
+        Some(NameMatch::Exact)
+    } else if name.starts_with(query) {
+        Some(NameMatch::Prefix)
+    } else if name.to_lowercase().contains(query_lower) {
+        Some(NameMatch::Substring)
+    } else {
+        None
+    }
+}
+
+/// an index of function ids by function name, and by name qualified by module
+pub struct NameIndex {
+    names: BTreeMap<String, Vec<i64>>,
+}
+
+impl NameIndex {
+    fn new() -> Self {
+        NameIndex {
+            names: BTreeMap::new(),
+        }
+    }
+
+    /// returns at most num ids of functions whose name matches name at least as closely as
+    /// loosest, starting at index starting_index
+    ///
+    /// exact matches come first, followed by names starting with name, then names containing
+    /// name ignoring case, names which match equally well are sorted alphabetically
+    pub fn search(
+        &self,
+        name: &str,
+        loosest: NameMatch,
+        num: usize,
+        starting_index: Option<usize>,
+    ) -> Page<i64> {
+        let candidates: Box<dyn Iterator<Item = (&String, &Vec<i64>)>> = match loosest {
+            NameMatch::Exact => Box::new(self.names.get_key_value(name).into_iter()),
+            NameMatch::Prefix => Box::new(
+                self.names
+                    .range::<str, _>((Bound::Included(name), Bound::Unbounded))
+                    .take_while(move |(key, _)| key.starts_with(name)),
+            ),
+            NameMatch::Substring => Box::new(self.names.iter()),
+        };
+        let name_lower = name.to_lowercase();
+        let mut matches: Vec<(NameMatch, &Vec<i64>)> = candidates
+            .filter_map(|(key, ids)| match_name(name, &name_lower, key).map(|m| (m, ids)))
+            .filter(|(m, _)| *m <= loosest)
+            .collect();
+        // the sort is stable, so names which match equally well stay in alphabetical order
+        matches.sort_by_key(|(m, _)| *m);
+        // functions are indexed by their name and their qualified name, which may both match,
+        // only the closest match is kept
+        let mut seen = HashSet::new();
+        let ids: Vec<i64> = matches
+            .into_iter()
+            .flat_map(|(_, ids)| ids.iter().cloned())
+            .filter(|id| seen.insert(*id))
+            .collect();
+        let total = ids.len();
+        let items = ids
+            .into_iter()
+            .skip(starting_index.unwrap_or(0))
+            .take(num)
+            .collect();
+        Page { items, total }
+    }
+
+    fn insert(&mut self, name: &str, func_id: i64) {
+        self.names
+            .entry(name.to_string())
+            .or_default()
+            .push(func_id);
+    }
+
+    /// inserts a function by its name and by its name qualified by module, `List.foldl`
+    fn insert_function(&mut self, module: &str, name: &str, func_id: i64) {
+        self.insert(name, func_id);
+        if !module.is_empty() {
+            self.insert(&format!("{}.{}", module, name), func_id);
+        }
+    }
+}
+
+impl FromIterator<(String, i64)> for NameIndex {
+    fn from_iter<T: IntoIterator<Item = (String, i64)>>(fns: T) -> Self {
+        let mut c = NameIndex::new();
+        for f in fns {
+            c.insert(f.0.as_str(), f.1);
+        }
+        c
+    }
+}
+
+impl<'a> FromIterator<(&'a str, i64)> for NameIndex {
+    fn from_iter<T: IntoIterator<Item = (&'a str, i64)>>(fns: T) -> Self {
+        let mut c = NameIndex::new();
+        for f in fns {
+            c.insert(f.0, f.1);
+        }
+        c
+    }
+}
+
+/// functions by module, name and id
+impl FromIterator<(String, String, i64)> for NameIndex {
+    fn from_iter<T: IntoIterator<Item = (String, String, i64)>>(fns: T) -> Self {
+        let mut c = NameIndex::new();
+        for f in fns {
+            c.insert_function(f.0.as_str(), f.1.as_str(), f.2);
+        }
+        c
+    }
+}
@@ -9,65 +9,358 @@ static GLOBAL: Jemalloc = Jemalloc;
 
 pub(crate) mod app_state;
 pub(crate) mod collections;
+pub(crate) mod matching;
 pub(crate) mod queries;
+pub(crate) mod responses;
+pub(crate) mod search_query;
 #[cfg(test)]
 mod tests;
+pub(crate) mod version;
 
 use crate::app_state::AppState;
-use crate::collections::FnCache;
+use crate::collections::{FnCache, NameIndex, NameMatch, Page};
 use crate::queries::functions::*;
-use crate::queries::make_fn_cache;
+use crate::queries::repositories::get_repositories;
+use crate::queries::types::get_types_by_name;
+use crate::queries::{make_fn_cache, make_name_index};
+use crate::responses::{PageResponse, SearchResult, TextSearchResult, TypeResult};
+use crate::search_query::parse_query;
 use actix_web::Responder;
 use actix_web::{
-    error::ErrorInternalServerError, middleware::cors::Cors, middleware::Logger, server, App,
-    HttpRequest, Result,
+    error::ErrorBadRequest, error::ErrorInternalServerError, middleware::cors::Cors,
+    middleware::Logger, server, App, FromRequest, HttpRequest, Query, Result,
 };
 use fn_search_backend::get_config;
+use fn_search_backend_db::models::FunctionWithRepo;
 use fn_search_backend_db::utils::get_db_url;
 use percent_encoding::percent_decode;
 use r2d2::Pool;
 use r2d2_diesel::ConnectionManager;
+use serde_derive::Deserialize;
+use std::collections::HashSet;
 use std::sync::Arc;
 
-fn search(req: &HttpRequest<AppState>) -> Result<impl Responder> {
-    let sig: String = req.match_info().query("type_signature")?;
-    let sig = percent_decode(sig.as_bytes()).decode_utf8()?.to_string();
-    let cache: Arc<FnCache> = req.state().get_fn_cache();
-    let conn = req
-        .state()
-        .db_conn()
-        .map_err(|e| ErrorInternalServerError(e))?;
-    let res = (*cache).search(sig.as_str(), 10, None);
-    Ok(match res {
-        Some(ids) => {
-            let funcs = get_functions(&conn, ids).map_err(|e| ErrorInternalServerError(e))?;
-            serde_json::to_string(funcs.as_slice())?
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum SearchMode {
+    /// functions with the same type signature, up to the names of type variables
+    #[default]
+    Exact,
+    /// functions with a type signature which unifies with the query, exact matches first
+    Unify,
+    /// like unify, but the arguments of the query may be given in any order
+    AnyOrder,
+    /// functions ranked by the distance of their type signature from the query,
+    /// returned along with their score
+    Approximate,
+}
+
+/// number of results returned when no limit is given
+const DEFAULT_PAGE_SIZE: usize = 10;
+/// the largest number of results which can be requested at once
+const MAX_PAGE_SIZE: usize = 100;
+
+/// returns the offset and limit of the page to return, limits are capped at MAX_PAGE_SIZE
+fn page_bounds(offset: Option<usize>, limit: Option<usize>) -> (usize, usize) {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    (offset.unwrap_or(0), limit)
+}
+
+#[derive(Deserialize)]
+struct SearchOptions {
+    #[serde(default)]
+    mode: SearchMode,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    /// the same as the `package:` filter in the query
+    package: Option<String>,
+    /// the same as the `author:` filter in the query
+    author: Option<String>,
+    /// the same as the `version:` filter in the query
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NameSearchOptions {
+    #[serde(default)]
+    mode: NameMatch,
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct PageOptions {
+    offset: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// every function whose type signature matches sig, best matches first, along with their
+/// score for approximate searches
+///
+/// functions are matched against their signature as written and with type aliases expanded,
+/// and are returned once with their best match
+fn type_matches(cache: &FnCache, sig: &str, mode: SearchMode) -> Vec<(i64, Option<u32>)> {
+    let all = |page: Option<Page<i64>>| {
+        page.map_or_else(Vec::new, |p| {
+            p.items.into_iter().map(|id| (id, None)).collect()
+        })
+    };
+    let mut matches: Vec<(i64, Option<u32>)> = match mode {
+        // records in the query match records with any additional fields
+        SearchMode::Exact => match cache.search_record_fields(sig, usize::MAX, None) {
+            Some(page) => all(Some(page)),
+            None => cache
+                .search(sig, cache.count(sig), None)
+                .unwrap_or_default()
+                .iter()
+                .map(|id| (*id, None))
+                .collect(),
+        },
+        SearchMode::Unify => all(cache.search_unifiable(sig, usize::MAX, None)),
+        SearchMode::AnyOrder => all(cache.search_any_order(sig, usize::MAX, None)),
+        SearchMode::Approximate => {
+            cache
+                .search_approximate(sig, usize::MAX, None)
+                .map_or_else(Vec::new, |p| {
+                    p.items
+                        .into_iter()
+                        .map(|(id, score)| (id, Some(score)))
+                        .collect()
+                })
+        }
+    };
+    let mut seen = HashSet::new();
+    matches.retain(|(id, _)| seen.insert(*id));
+    matches
+}
+
+/// a page of results found by a handler, along with the number of results across all pages
+/// and the offset of the page
+struct FoundPage<T> {
+    items: Vec<T>,
+    total: usize,
+    offset: usize,
+}
+
+/// the functions matching the search query in the request, along with their score
+fn find_functions(
+    req: &HttpRequest<AppState>,
+) -> Result<FoundPage<(FunctionWithRepo, Option<u32>)>> {
+    let query: String = req.match_info().query("query")?;
+    let query = percent_decode(query.as_bytes()).decode_utf8()?.to_string();
+    let mut query = parse_query(query.as_str()).map_err(ErrorBadRequest)?;
+    let options = Query::<SearchOptions>::extract(req)?;
+    let filters = [
+        ("package", &options.package),
+        ("author", &options.author),
+        ("version", &options.version),
+    ];
+    for (filter, value) in filters.iter() {
+        if let Some(value) = value {
+            query
+                .add_filter(filter, value.as_str())
+                .map_err(ErrorBadRequest)?;
+        }
+    }
+    let (offset, limit) = page_bounds(options.offset, options.limit);
+    let names: Arc<NameIndex> = req.state().get_name_index();
+    let name_matches = |name: &str| {
+        names
+            .search(name, NameMatch::default(), usize::MAX, None)
+            .items
+    };
+    let mut matches: Vec<(i64, Option<u32>)> = match (&query.type_signature, &query.name) {
+        (Some(sig), name) => {
+            let cache: Arc<FnCache> = req.state().get_fn_cache();
+            let mut matches = type_matches(&cache, sig.as_str(), options.mode);
+            if let Some(name) = name {
+                let ids: HashSet<i64> = name_matches(name.as_str()).into_iter().collect();
+                matches.retain(|(id, _)| ids.contains(id));
+            }
+            matches
         }
-        None => String::from("[]"),
+        (None, Some(name)) => name_matches(name.as_str())
+            .into_iter()
+            .map(|id| (id, None))
+            .collect(),
+        (None, None) => Vec::new(),
+    };
+    let conn = req.state().db_conn().map_err(ErrorInternalServerError)?;
+    // filter before paginating so every page is full
+    if query.has_package_filters() {
+        let repo_ids: Vec<i32> = get_repositories(&conn)
+            .map_err(ErrorInternalServerError)?
+            .into_iter()
+            .filter(|r| query.matches_package(r.name.as_str(), r.ver.as_str()))
+            .map(|r| r.id)
+            .collect();
+        let ids: HashSet<i64> = get_function_ids_in_repos(&conn, &repo_ids)
+            .map_err(ErrorInternalServerError)?
+            .into_iter()
+            .collect();
+        matches.retain(|(id, _)| ids.contains(id));
+    }
+    if let Some(module) = &query.module {
+        let ids: HashSet<i64> = get_function_ids_in_module(&conn, module.as_str())
+            .map_err(ErrorInternalServerError)?
+            .into_iter()
+            .collect();
+        matches.retain(|(id, _)| ids.contains(id));
+    }
+    let total = matches.len();
+    let page: Vec<(i64, Option<u32>)> = matches.into_iter().skip(offset).take(limit).collect();
+    let funcs = if page.is_empty() {
+        Vec::new()
+    } else {
+        let ids: Vec<i64> = page.iter().map(|(id, _)| *id).collect();
+        get_functions(&conn, &ids).map_err(ErrorInternalServerError)?
+    };
+    let funcs = funcs
+        .into_iter()
+        .map(|function| {
+            let score = page
+                .iter()
+                .find(|(id, _)| *id == function.func_id)
+                .and_then(|(_, score)| *score);
+            (function, score)
+        })
+        .collect();
+    Ok(FoundPage {
+        items: funcs,
+        total,
+        offset,
     })
 }
 
-fn suggest(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+fn search(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let found = find_functions(req)?;
+    let results: Vec<SearchResult> = found
+        .items
+        .into_iter()
+        .map(|(function, score)| SearchResult::new(function, score))
+        .collect();
+    Ok(serde_json::to_string(&PageResponse::new(
+        results,
+        found.total,
+        found.offset,
+    ))?)
+}
+
+/// search returning a flat list of functions, for `/v0` clients
+fn search_v0(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let funcs: Vec<FunctionWithRepo> = find_functions(req)?
+        .items
+        .into_iter()
+        .map(|(f, _)| f)
+        .collect();
+    Ok(serde_json::to_string(funcs.as_slice())?)
+}
+
+/// the type signatures completing the type signature in the request
+fn find_suggestions(req: &HttpRequest<AppState>) -> Result<FoundPage<String>> {
     let sig: String = req.match_info().query("type_signature")?;
     let sig = percent_decode(sig.as_bytes()).decode_utf8()?.to_string();
+    let options = Query::<PageOptions>::extract(req)?;
+    let (offset, limit) = page_bounds(options.offset, options.limit);
     let cache: Arc<FnCache> = req.state().get_fn_cache();
-    let res = (*cache).suggest(sig.as_str(), 10);
-    Ok(match res {
-        Some(sigs) => serde_json::to_string(sigs.as_slice())?,
-        None => String::from("[]"),
+    let sigs = cache
+        .suggest(sig.as_str(), limit, Some(offset))
+        .unwrap_or_default()
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let total = cache.suggest_count(sig.as_str());
+    Ok(FoundPage {
+        items: sigs,
+        total,
+        offset,
     })
 }
 
+fn suggest(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let found = find_suggestions(req)?;
+    Ok(serde_json::to_string(&PageResponse::new(
+        found.items,
+        found.total,
+        found.offset,
+    ))?)
+}
+
+/// suggest returning a list of type signatures, for `/v0` clients
+fn suggest_v0(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let sigs = find_suggestions(req)?.items;
+    Ok(serde_json::to_string(sigs.as_slice())?)
+}
+
+fn search_name(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let name: String = req.match_info().query("name")?;
+    let name = percent_decode(name.as_bytes()).decode_utf8()?.to_string();
+    let options = Query::<NameSearchOptions>::extract(req)?;
+    let (offset, limit) = page_bounds(options.offset, options.limit);
+    let names: Arc<NameIndex> = req.state().get_name_index();
+    let page = names.search(name.as_str(), options.mode, limit, Some(offset));
+    let funcs = if page.items.is_empty() {
+        Vec::new()
+    } else {
+        let conn = req.state().db_conn().map_err(ErrorInternalServerError)?;
+        get_functions(&conn, &page.items).map_err(ErrorInternalServerError)?
+    };
+    let results: Vec<SearchResult> = funcs
+        .into_iter()
+        .map(|function| SearchResult::new(function, None))
+        .collect();
+    Ok(serde_json::to_string(&PageResponse::new(
+        results, page.total, offset,
+    ))?)
+}
+
+fn search_text(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let text: String = req.match_info().query("text")?;
+    let text = percent_decode(text.as_bytes()).decode_utf8()?.to_string();
+    let options = Query::<PageOptions>::extract(req)?;
+    let (offset, limit) = page_bounds(options.offset, options.limit);
+    let conn = req.state().db_conn().map_err(ErrorInternalServerError)?;
+    let page = search_function_text(&conn, text.as_str(), limit as i64, offset as i64)
+        .map_err(ErrorInternalServerError)?;
+    let results: Vec<TextSearchResult> =
+        page.items.into_iter().map(TextSearchResult::new).collect();
+    Ok(serde_json::to_string(&PageResponse::new(
+        results, page.total, offset,
+    ))?)
+}
+
+fn search_type(req: &HttpRequest<AppState>) -> Result<impl Responder> {
+    let name: String = req.match_info().query("name")?;
+    let name = percent_decode(name.as_bytes()).decode_utf8()?.to_string();
+    // a qualified name such as `Dict.Dict` is the type `Dict` in the module `Dict`
+    let (module, name) = match name.rfind('.') {
+        Some(i) => (Some(&name[..i]), &name[i + 1..]),
+        None => (None, name.as_str()),
+    };
+    let conn = req.state().db_conn().map_err(ErrorInternalServerError)?;
+    let results: Vec<TypeResult> = get_types_by_name(&conn, name, module)
+        .map_err(ErrorInternalServerError)?
+        .into_iter()
+        .map(|(t, r)| TypeResult::new(t, r))
+        .collect();
+    let total = results.len();
+    Ok(serde_json::to_string(&PageResponse::new(
+        results, total, 0,
+    ))?)
+}
+
 fn update_fns(req: &HttpRequest<AppState>) -> Result<impl Responder> {
-    let sigs = {
-        let conn = req
-            .state()
-            .db_conn()
-            .map_err(|e| ErrorInternalServerError(e))?;
-        get_all_func_sigs(&(*conn)).map_err(|e| ErrorInternalServerError(e))?
+    let (sigs, names) = {
+        let conn = req.state().db_conn().map_err(ErrorInternalServerError)?;
+        (
+            get_all_func_sigs(&conn).map_err(ErrorInternalServerError)?,
+            get_all_func_names(&conn).map_err(ErrorInternalServerError)?,
+        )
     }; // database connection goes out of scope, returning to pool
     let fn_cache: FnCache = sigs.into_iter().collect();
+    let name_index: NameIndex = names.into_iter().collect();
     req.state().update_fn_cache(fn_cache);
+    req.state().update_name_index(name_index);
     Ok("OK")
 }
 
@@ -89,7 +382,7 @@ fn main() {
         _ => panic!("unknown log level"),
     };
 
-    if log_str != "" {
+    if !log_str.is_empty() {
         std::env::set_var("RUST_LOG", log_str);
     }
     env_logger::init();
@@ -97,7 +390,7 @@ fn main() {
     let cfg_file = matches
         .value_of("CONFIG")
         .expect("error parsing configuration file");
-    let cfg = get_config(&cfg_file).expect("error loading configuration file");
+    let cfg = get_config(cfg_file).expect("error loading configuration file");
     let cfg = Arc::new(cfg);
 
     let pool = Pool::builder()
@@ -105,20 +398,36 @@ fn main() {
         .build(ConnectionManager::new(get_db_url(&cfg.clone().db)))
         .expect("error setting up database connection");
 
-    let fn_cache = make_fn_cache(&*pool.get().expect("error connecting to database"));
+    let conn = pool.get().expect("error connecting to database");
+    let fn_cache = make_fn_cache(&conn);
     let cache = Arc::new(fn_cache.expect("error retrieving function type signatures"));
+    let name_index = make_name_index(&conn);
+    let names = Arc::new(name_index.expect("error retrieving function names"));
+    drop(conn);
 
     let cfg_clone = cfg.clone();
     server::new(move || {
-        App::with_state(AppState::new(pool.clone(), cache.clone())).configure(|app| {
-            Cors::for_app(app)
-                .allowed_origin(&cfg.web.allowed_origin)
-                .resource("/search/{type_signature}", |r| r.f(search))
-                .resource("/suggest/{type_signature}", |r| r.f(suggest))
-                .resource("/update_functions", |r| r.f(update_fns))
-                .register()
-                .middleware(Logger::default())
-        })
+        App::with_state(AppState::new(pool.clone(), cache.clone(), names.clone())).configure(
+            |app| {
+                Cors::for_app(app)
+                    .allowed_origin(&cfg.web.allowed_origin)
+                    .resource("/search/{query}", |r| r.f(search))
+                    .resource("/suggest/{type_signature}", |r| r.f(suggest))
+                    .resource("/search_name/{name}", |r| r.f(search_name))
+                    .resource("/search_text/{text}", |r| r.f(search_text))
+                    .resource("/types/{name}", |r| r.f(search_type))
+                    .resource("/v1/search/{query}", |r| r.f(search))
+                    .resource("/v1/suggest/{type_signature}", |r| r.f(suggest))
+                    .resource("/v1/search_name/{name}", |r| r.f(search_name))
+                    .resource("/v1/search_text/{text}", |r| r.f(search_text))
+                    .resource("/v1/types/{name}", |r| r.f(search_type))
+                    .resource("/v0/search/{query}", |r| r.f(search_v0))
+                    .resource("/v0/suggest/{type_signature}", |r| r.f(suggest_v0))
+                    .resource("/update_functions", |r| r.f(update_fns))
+                    .register()
+                    .middleware(Logger::default())
+            },
+        )
     })
     .bind(&cfg_clone.web.bind_address)
     .unwrap()
@@ -0,0 +1,9 @@
+pub mod distance;
+pub mod permute;
+pub mod records;
+pub mod unify;
+
+pub use crate::matching::distance::{type_distance, MAX_DISTANCE};
+pub use crate::matching::permute::match_permuted;
+pub use crate::matching::records::{has_records, match_record_fields};
+pub use crate::matching::unify::match_types;
@@ -0,0 +1,112 @@
+use fn_search_backend_parsers::ElmType;
+use std::cmp::min;
+
+/// cost of two types which have nothing in common
+const MISMATCH: u32 = 5;
+/// cost of an argument which is missing from one of the signatures
+const MISSING_ARG: u32 = 4;
+/// cost of wrapping a type in a `Maybe` or `Result`
+const WRAPPED: u32 = 2;
+/// cost of a stored signature using a concrete type where the query has a type variable
+const SPECIALIZED: u32 = 2;
+/// cost of a stored signature using a type variable where the query has a concrete type
+const GENERALIZED: u32 = 1;
+/// cost of a record field which is missing from one of the records
+const MISSING_FIELD: u32 = 2;
+
+/// signatures further than this from the query are not considered a match
+pub const MAX_DISTANCE: u32 = 10;
+
+/// estimate how different a stored type signature is from the query, 0 being the same
+///
+/// the distance accounts for missing or extra arguments, differing type constructors,
+/// types wrapped in `Maybe`/`Result` and type variables in place of concrete types
+pub fn type_distance(query: &ElmType, stored: &ElmType) -> u32 {
+    let distance = match (query, stored) {
+        (ElmType::Variable(_), ElmType::Variable(_)) => 0,
+        (_, ElmType::Variable(_)) => GENERALIZED,
+        (ElmType::Variable(_), _) => SPECIALIZED,
+        (ElmType::Function { args: a1, ret: r1 }, ElmType::Function { args: a2, ret: r2 }) => {
+            align(a1, a2) + type_distance(r1, r2)
+        }
+        (ElmType::Function { args, ret }, _) => align(args, &[]) + type_distance(ret, stored),
+        (_, ElmType::Function { args, ret }) => align(&[], args) + type_distance(query, ret),
+        (
+            ElmType::Constructor { name: n1, args: a1 },
+            ElmType::Constructor { name: n2, args: a2 },
+        ) if unqualified(n1) == unqualified(n2) => align(a1, a2),
+        (ElmType::Tuple(t1), ElmType::Tuple(t2)) if t1.len() == t2.len() => align(t1, t2),
+        (ElmType::Record(f1), ElmType::Record(f2))
+        | (ElmType::Record(f1), ElmType::ExtensibleRecord { fields: f2, .. })
+        | (ElmType::ExtensibleRecord { fields: f1, .. }, ElmType::Record(f2))
+        | (
+            ElmType::ExtensibleRecord { fields: f1, .. },
+            ElmType::ExtensibleRecord { fields: f2, .. },
+        ) => field_distance(f1, f2),
+        (ElmType::Unit, ElmType::Unit) => 0,
+        _ => MISMATCH,
+    };
+    if distance <= WRAPPED {
+        return distance;
+    }
+    // `a` is close to `Maybe a` and `Result e a`, in either direction
+    let unwrapped = match (wrapped_type(query), wrapped_type(stored)) {
+        (_, Some(inner)) => WRAPPED + type_distance(query, inner),
+        (Some(inner), None) => WRAPPED + type_distance(inner, stored),
+        (None, None) => distance,
+    };
+    min(distance, unwrapped)
+}
+
+/// the type inside of a `Maybe` or `Result`
+fn wrapped_type(typ: &ElmType) -> Option<&ElmType> {
+    match typ {
+        ElmType::Constructor { name, args } => match (unqualified(name), args.as_slice()) {
+            ("Maybe", [inner]) | ("Result", [_, inner]) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// the name of a type without the module it is in, `Dict.Dict` becomes `Dict`
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// the edit distance between two lists of types, where substituting one type for another
+/// costs their type distance
+fn align(query: &[ElmType], stored: &[ElmType]) -> u32 {
+    // distances[j] is the distance between the query types seen so far and stored[..j]
+    let mut distances: Vec<u32> = (0..=stored.len() as u32).map(|j| j * MISSING_ARG).collect();
+    for (i, q) in query.iter().enumerate() {
+        let mut diagonal = distances[0];
+        distances[0] = (i as u32 + 1) * MISSING_ARG;
+        for (j, s) in stored.iter().enumerate() {
+            let substituted = diagonal + type_distance(q, s);
+            diagonal = distances[j + 1];
+            distances[j + 1] = min(
+                substituted,
+                min(distances[j + 1], distances[j]) + MISSING_ARG,
+            );
+        }
+    }
+    distances[stored.len()]
+}
+
+fn field_distance(query: &[(String, ElmType)], stored: &[(String, ElmType)]) -> u32 {
+    let missing = stored
+        .iter()
+        .filter(|(name, _)| !query.iter().any(|(other, _)| other == name))
+        .count() as u32;
+    query
+        .iter()
+        .map(
+            |(name, q)| match stored.iter().find(|(other, _)| other == name) {
+                Some((_, s)) => type_distance(q, s),
+                None => MISSING_FIELD,
+            },
+        )
+        .sum::<u32>()
+        + missing * MISSING_FIELD
+}
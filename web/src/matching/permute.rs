@@ -0,0 +1,80 @@
+use crate::matching::unify::{match_types, TypeMatch};
+use fn_search_backend_parsers::ElmType;
+
+/// functions with more arguments than this are only matched in their original order,
+/// since the number of permutations grows factorially
+const MAX_PERMUTED_ARGS: usize = 6;
+
+/// find how stored relates to query when the arguments of query may be given in any order
+///
+/// returns the smallest permutation distance (the number of swaps of adjacent arguments needed
+/// to turn query into stored) along with how the reordered query matches stored,
+/// or None if no ordering of the arguments unifies with stored
+pub fn match_permuted(query: &ElmType, stored: &ElmType) -> Option<(usize, TypeMatch)> {
+    let (query_args, query_ret, stored_ret) = match (query, stored) {
+        (
+            ElmType::Function {
+                args: query_args,
+                ret: query_ret,
+            },
+            ElmType::Function {
+                args: stored_args,
+                ret: stored_ret,
+            },
+        ) if query_args.len() == stored_args.len() => (query_args, query_ret, stored_ret),
+        _ => return match_types(query, stored).map(|m| (0, m)),
+    };
+    // cheap check before trying every ordering
+    match_types(query_ret, stored_ret)?;
+    let mut orderings = if query_args.len() <= MAX_PERMUTED_ARGS {
+        permutations(query_args.len())
+    } else {
+        vec![(0..query_args.len()).collect()]
+    };
+    orderings.sort_by_key(|p| inversions(p));
+    let mut best: Option<(usize, TypeMatch)> = None;
+    for ordering in orderings {
+        let distance = inversions(&ordering);
+        if let Some((best_distance, _)) = best {
+            if distance > best_distance {
+                break;
+            }
+        }
+        let permuted = ElmType::Function {
+            args: ordering.iter().map(|i| query_args[*i].clone()).collect(),
+            ret: query_ret.clone(),
+        };
+        if let Some(m) = match_types(&permuted, stored) {
+            best = match best {
+                Some((_, best_match)) if best_match <= m => best,
+                _ => Some((distance, m)),
+            };
+        }
+    }
+    best
+}
+
+/// every ordering of the indices 0..n
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    let mut res = Vec::new();
+    for p in permutations(n - 1) {
+        for i in 0..n {
+            let mut p = p.clone();
+            p.insert(i, n - 1);
+            res.push(p);
+        }
+    }
+    res
+}
+
+/// the number of pairs which are out of order, which is the number of swaps of adjacent
+/// elements needed to sort p
+fn inversions(p: &[usize]) -> usize {
+    p.iter()
+        .enumerate()
+        .map(|(i, a)| p[i + 1..].iter().filter(|b| a > b).count())
+        .sum()
+}
@@ -0,0 +1,105 @@
+use crate::matching::unify::constraint;
+use fn_search_backend_parsers::ElmType;
+use std::collections::HashMap;
+
+/// true if typ mentions a record or an extensible record
+pub fn has_records(typ: &ElmType) -> bool {
+    match typ {
+        ElmType::Record(_) | ElmType::ExtensibleRecord { .. } => true,
+        ElmType::Constructor { args, .. } | ElmType::Tuple(args) => args.iter().any(has_records),
+        ElmType::Function { args, ret } => args.iter().any(has_records) || has_records(ret),
+        ElmType::Variable(_) | ElmType::Unit => false,
+    }
+}
+
+/// match stored against query, where records in stored may have fields the records in query
+/// don't mention, so `{ name : String } -> String` matches `{ age : Int, name : String } -> String`
+///
+/// apart from the additional fields the signatures must be the same, up to the names of type
+/// variables. Returns the number of additional fields, or None if the signatures don't match
+pub fn match_record_fields(query: &ElmType, stored: &ElmType) -> Option<usize> {
+    let mut matcher = FieldMatcher::default();
+    if matcher.matches(query, stored) {
+        Some(matcher.extra_fields)
+    } else {
+        None
+    }
+}
+
+/// renames the variables of the query to the variables of the stored signature
+#[derive(Default)]
+struct FieldMatcher {
+    to_stored: HashMap<String, String>,
+    to_query: HashMap<String, String>,
+    extra_fields: usize,
+}
+
+impl FieldMatcher {
+    /// variables must be renamed one to one, and keep their constraint
+    fn rename(&mut self, query: &str, stored: &str) -> bool {
+        if constraint(query) != constraint(stored) {
+            return false;
+        }
+        let q = self
+            .to_stored
+            .entry(query.to_string())
+            .or_insert_with(|| stored.to_string());
+        let s = self
+            .to_query
+            .entry(stored.to_string())
+            .or_insert_with(|| query.to_string());
+        q == stored && s == query
+    }
+
+    fn matches_all(&mut self, query: &[ElmType], stored: &[ElmType]) -> bool {
+        query.len() == stored.len()
+            && query
+                .iter()
+                .zip(stored.iter())
+                .all(|(q, s)| self.matches(q, s))
+    }
+
+    /// every field of query must be in stored with a matching type
+    fn matches_fields(
+        &mut self,
+        query: &[(String, ElmType)],
+        stored: &[(String, ElmType)],
+    ) -> bool {
+        let found =
+            query.iter().all(
+                |(name, q)| match stored.iter().find(|(other, _)| other == name) {
+                    Some((_, s)) => self.matches(q, s),
+                    None => false,
+                },
+            );
+        self.extra_fields += stored.len().saturating_sub(query.len());
+        found
+    }
+
+    fn matches(&mut self, query: &ElmType, stored: &ElmType) -> bool {
+        match (query, stored) {
+            (ElmType::Variable(q), ElmType::Variable(s)) => self.rename(q, s),
+            (
+                ElmType::Constructor { name: n1, args: a1 },
+                ElmType::Constructor { name: n2, args: a2 },
+            ) => n1 == n2 && self.matches_all(a1, a2),
+            (ElmType::Function { args: a1, ret: r1 }, ElmType::Function { args: a2, ret: r2 }) => {
+                self.matches_all(a1, a2) && self.matches(r1, r2)
+            }
+            (ElmType::Tuple(t1), ElmType::Tuple(t2)) => self.matches_all(t1, t2),
+            (ElmType::Record(f1), ElmType::Record(f2)) => self.matches_fields(f1, f2),
+            (
+                ElmType::ExtensibleRecord {
+                    base: b1,
+                    fields: f1,
+                },
+                ElmType::ExtensibleRecord {
+                    base: b2,
+                    fields: f2,
+                },
+            ) => self.rename(b1, b2) && self.matches_fields(f1, f2),
+            (ElmType::Unit, ElmType::Unit) => true,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use fn_search_backend_parsers::{ElmType, CONSTRAINED_VARIABLES};
+use std::collections::{HashMap, HashSet};
+
+/// How a stored type signature relates to the type signature being searched for.
+///
+/// Variants are ordered from most to least relevant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TypeMatch {
+    /// the signatures are the same, up to the names of type variables
+    Exact,
+    /// the stored signature is more general, the query is an instance of it
+    /// (`List a -> Int` when searching for `List String -> Int`)
+    Generalization,
+    /// the stored signature is an instance of the query
+    /// (`List String -> Int` when searching for `List a -> Int`)
+    Instance,
+    /// both signatures can be specialized to a common type
+    Unifiable,
+}
+
+/// find how stored relates to query, returns None if the two types do not unify
+///
+/// records in query also match records with additional fields, so `{ name : String } -> String`
+/// finds `{ name : String, age : Int } -> String` as an instance of the query
+pub fn match_types(query: &ElmType, stored: &ElmType) -> Option<TypeMatch> {
+    match_exact_records(query, stored).or_else(|| {
+        let open = open_records(query);
+        if &open == query {
+            return None;
+        }
+        match_exact_records(&open, stored)
+            .filter(|m| *m == TypeMatch::Exact || *m == TypeMatch::Instance)
+            .map(|_| TypeMatch::Instance)
+    })
+}
+
+fn match_exact_records(query: &ElmType, stored: &ElmType) -> Option<TypeMatch> {
+    let query = rename_apart(query, "'q");
+    let stored = rename_apart(stored, "'s");
+    let query_vars = variables(&query);
+    let stored_vars = variables(&stored);
+    let no_vars = HashSet::new();
+    let is_instance = |general: &ElmType, specific: &ElmType, rigid: &HashSet<String>| {
+        Unifier::new(rigid).unify(general, specific)
+    };
+    match (
+        is_instance(&stored, &query, &query_vars),
+        is_instance(&query, &stored, &stored_vars),
+    ) {
+        (true, true) => Some(TypeMatch::Exact),
+        (true, false) => Some(TypeMatch::Generalization),
+        (false, true) => Some(TypeMatch::Instance),
+        (false, false) if Unifier::new(&no_vars).unify(&query, &stored) => {
+            Some(TypeMatch::Unifiable)
+        }
+        _ => None,
+    }
+}
+
+pub fn constraint(var: &str) -> Option<&'static str> {
+    CONSTRAINED_VARIABLES
+        .iter()
+        .find(|class| var.starts_with(*class))
+        .cloned()
+}
+
+/// true if every type in the class `narrow` is also in the class `wide`
+fn is_subclass(narrow: &str, wide: &str) -> bool {
+    match (narrow, wide) {
+        (n, w) if n == w => true,
+        ("number", "comparable") => true,
+        ("compappend", "comparable") => true,
+        ("compappend", "appendable") => true,
+        _ => false,
+    }
+}
+
+/// true if typ can be substituted for a variable constrained to class
+fn satisfies(class: &str, typ: &ElmType) -> bool {
+    let name = match typ {
+        ElmType::Constructor { name, .. } => name.rsplit('.').next().unwrap_or_default(),
+        ElmType::Tuple(_) => return class == "comparable",
+        _ => return false,
+    };
+    match class {
+        "number" => name == "Int" || name == "Float",
+        "comparable" => ["Int", "Float", "Char", "String", "List"].contains(&name),
+        "appendable" | "compappend" => name == "String" || name == "List",
+        _ => false,
+    }
+}
+
+/// turns records into extensible records with a new base variable, so they match records with
+/// any additional fields, `{ name : String }` becomes `{ 'r0 | name : String }`
+fn open_records(typ: &ElmType) -> ElmType {
+    fn open(typ: &ElmType, count: &mut usize) -> ElmType {
+        let open_all = |types: &[ElmType], count: &mut usize| -> Vec<ElmType> {
+            types.iter().map(|t| open(t, count)).collect()
+        };
+        match typ {
+            ElmType::Constructor { name, args } => ElmType::Constructor {
+                name: name.clone(),
+                args: open_all(args, count),
+            },
+            ElmType::Function { args, ret } => ElmType::Function {
+                args: open_all(args, count),
+                ret: Box::new(open(ret, count)),
+            },
+            ElmType::Tuple(types) => ElmType::Tuple(open_all(types, count)),
+            ElmType::Record(fields) if !fields.is_empty() => {
+                let base = format!("'r{}", count);
+                *count += 1;
+                ElmType::ExtensibleRecord {
+                    base,
+                    fields: fields
+                        .iter()
+                        .map(|(name, t)| (name.clone(), open(t, count)))
+                        .collect(),
+                }
+            }
+            t => t.clone(),
+        }
+    }
+    open(typ, &mut 0)
+}
+
+/// add a suffix to all type variables, so variables from different signatures don't collide
+fn rename_apart(typ: &ElmType, suffix: &str) -> ElmType {
+    map_variables(typ, &|var| format!("{}{}", var, suffix))
+}
+
+fn map_variables<F: Fn(&str) -> String>(typ: &ElmType, f: &F) -> ElmType {
+    let map_fields = |fields: &[(String, ElmType)]| {
+        fields
+            .iter()
+            .map(|(name, t)| (name.clone(), map_variables(t, f)))
+            .collect()
+    };
+    match typ {
+        ElmType::Constructor { name, args } => ElmType::Constructor {
+            name: name.clone(),
+            args: args.iter().map(|t| map_variables(t, f)).collect(),
+        },
+        ElmType::Variable(var) => ElmType::Variable(f(var)),
+        ElmType::Function { args, ret } => ElmType::Function {
+            args: args.iter().map(|t| map_variables(t, f)).collect(),
+            ret: Box::new(map_variables(ret, f)),
+        },
+        ElmType::Tuple(types) => {
+            ElmType::Tuple(types.iter().map(|t| map_variables(t, f)).collect())
+        }
+        ElmType::Record(fields) => ElmType::Record(map_fields(fields)),
+        ElmType::ExtensibleRecord { base, fields } => ElmType::ExtensibleRecord {
+            base: f(base),
+            fields: map_fields(fields),
+        },
+        ElmType::Unit => ElmType::Unit,
+    }
+}
+
+/// all type variables appearing in typ
+fn variables(typ: &ElmType) -> HashSet<String> {
+    fn collect(typ: &ElmType, vars: &mut HashSet<String>) {
+        match typ {
+            ElmType::Variable(var) => {
+                vars.insert(var.clone());
+            }
+            ElmType::Constructor { args, .. } | ElmType::Tuple(args) => {
+                args.iter().for_each(|t| collect(t, vars))
+            }
+            ElmType::Function { args, ret } => {
+                args.iter().for_each(|t| collect(t, vars));
+                collect(ret, vars);
+            }
+            ElmType::Record(fields) => fields.iter().for_each(|(_, t)| collect(t, vars)),
+            ElmType::ExtensibleRecord { base, fields } => {
+                vars.insert(base.clone());
+                fields.iter().for_each(|(_, t)| collect(t, vars));
+            }
+            ElmType::Unit => {}
+        }
+    }
+    let mut vars = HashSet::new();
+    collect(typ, &mut vars);
+    vars
+}
+
+/// unifies types using a substitution from type variables to types
+struct Unifier<'a> {
+    substitution: HashMap<String, ElmType>,
+    /// the number of variables introduced while unifying extensible records
+    fresh: usize,
+    /// variables which may not be substituted, used to check if one type is an instance of another
+    rigid: &'a HashSet<String>,
+}
+
+impl<'a> Unifier<'a> {
+    fn new(rigid: &'a HashSet<String>) -> Self {
+        Unifier {
+            substitution: HashMap::new(),
+            fresh: 0,
+            rigid,
+        }
+    }
+
+    /// follow the substitution until typ is not a bound variable
+    fn walk<'b>(&'b self, typ: &'b ElmType) -> &'b ElmType {
+        let mut typ = typ;
+        while let ElmType::Variable(var) = typ {
+            match self.substitution.get(var) {
+                Some(t) => typ = t,
+                None => break,
+            }
+        }
+        typ
+    }
+
+    fn occurs(&self, var: &str, typ: &ElmType) -> bool {
+        match self.walk(typ) {
+            ElmType::Variable(v) => v == var,
+            ElmType::Constructor { args, .. } | ElmType::Tuple(args) => {
+                args.iter().any(|t| self.occurs(var, t))
+            }
+            ElmType::Function { args, ret } => {
+                args.iter().any(|t| self.occurs(var, t)) || self.occurs(var, ret)
+            }
+            ElmType::Record(fields) => fields.iter().any(|(_, t)| self.occurs(var, t)),
+            ElmType::ExtensibleRecord { base, fields } => {
+                base == var || fields.iter().any(|(_, t)| self.occurs(var, t))
+            }
+            ElmType::Unit => false,
+        }
+    }
+
+    fn bind(&mut self, var: &str, typ: &ElmType) -> bool {
+        if self.rigid.contains(var) || self.occurs(var, typ) {
+            return false;
+        }
+        if let Some(class) = constraint(var) {
+            let allowed = match typ {
+                ElmType::Variable(v) => match constraint(v) {
+                    Some(other) => is_subclass(other, class),
+                    // an unconstrained variable must take on the constraint instead
+                    None => {
+                        return !self.rigid.contains(v)
+                            && self.bind(v, &ElmType::Variable(var.to_string()))
+                    }
+                },
+                t => satisfies(class, t),
+            };
+            if !allowed {
+                return false;
+            }
+        }
+        self.substitution.insert(var.to_string(), typ.clone());
+        true
+    }
+
+    fn unify_all(&mut self, a: &[ElmType], b: &[ElmType]) -> bool {
+        a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| self.unify(a, b))
+    }
+
+    fn unify(&mut self, a: &ElmType, b: &ElmType) -> bool {
+        let a = self.walk(a).clone();
+        let b = self.walk(b).clone();
+        match (&a, &b) {
+            (ElmType::Variable(x), ElmType::Variable(y)) if x == y => true,
+            (ElmType::Variable(x), t) if !self.rigid.contains(x) => self.bind(x, t),
+            (t, ElmType::Variable(y)) if !self.rigid.contains(y) => self.bind(y, t),
+            (
+                ElmType::Constructor { name: n1, args: a1 },
+                ElmType::Constructor { name: n2, args: a2 },
+            ) => n1 == n2 && self.unify_all(a1, a2),
+            (ElmType::Function { args: a1, ret: r1 }, ElmType::Function { args: a2, ret: r2 }) => {
+                // functions are curried, `a -> b -> c` unifies with `x -> y` when y is `b -> c`
+                let n = a1.len().min(a2.len());
+                self.unify_all(&a1[..n], &a2[..n])
+                    && self.unify(
+                        &ElmType::function(a1[n..].to_vec(), (**r1).clone()),
+                        &ElmType::function(a2[n..].to_vec(), (**r2).clone()),
+                    )
+            }
+            (ElmType::Tuple(t1), ElmType::Tuple(t2)) => self.unify_all(t1, t2),
+            (ElmType::Record(f1), ElmType::Record(f2)) => self.unify_fields(f1, f2, true),
+            (ElmType::ExtensibleRecord { base, fields }, ElmType::Record(record))
+                if !self.rigid.contains(base) =>
+            {
+                // the base record is whatever fields the extensible record doesn't mention
+                let rest: Vec<(String, ElmType)> = record
+                    .iter()
+                    .filter(|(name, _)| !fields.iter().any(|(f, _)| f == name))
+                    .cloned()
+                    .collect();
+                // unified rather than bound, a base bound earlier must hold the same fields
+                self.unify_fields(fields, record, false)
+                    && self.unify(&ElmType::Variable(base.clone()), &ElmType::Record(rest))
+            }
+            (ElmType::Record(_), ElmType::ExtensibleRecord { .. }) => self.unify(&b, &a),
+            (
+                ElmType::ExtensibleRecord {
+                    base: b1,
+                    fields: f1,
+                },
+                ElmType::ExtensibleRecord {
+                    base: b2,
+                    fields: f2,
+                },
+            ) => {
+                let only_in = |a: &[(String, ElmType)], b: &[(String, ElmType)]| {
+                    a.iter()
+                        .filter(|(name, _)| !b.iter().any(|(other, _)| other == name))
+                        .cloned()
+                        .collect::<Vec<(String, ElmType)>>()
+                };
+                let (only1, only2) = (only_in(f1, f2), only_in(f2, f1));
+                let shared: Vec<(String, ElmType)> = f1
+                    .iter()
+                    .filter(|(name, _)| f2.iter().any(|(other, _)| other == name))
+                    .cloned()
+                    .collect();
+                if !self.unify_fields(&shared, f2, false) {
+                    return false;
+                }
+                // each base record holds the fields only the other record mentions
+                let base = |var: &str| ElmType::Variable(var.to_string());
+                let extend =
+                    |var: &str, fields: Vec<(String, ElmType)>| ElmType::ExtensibleRecord {
+                        base: var.to_string(),
+                        fields,
+                    };
+                match (only1.is_empty(), only2.is_empty()) {
+                    (true, true) => self.unify(&base(b1), &base(b2)),
+                    (true, false) => self.unify(&base(b1), &extend(b2, only2)),
+                    (false, true) => self.unify(&base(b2), &extend(b1, only1)),
+                    (false, false) => {
+                        let row = format!("'row{}", self.fresh);
+                        self.fresh += 1;
+                        self.unify(&base(b1), &extend(&row, only2))
+                            && self.unify(&base(b2), &extend(&row, only1))
+                    }
+                }
+            }
+            (ElmType::Unit, ElmType::Unit) => true,
+            _ => false,
+        }
+    }
+
+    /// unify the fields of sub with the fields of the same name in sup,
+    /// if exact is set both must have the same set of fields
+    fn unify_fields(
+        &mut self,
+        sub: &[(String, ElmType)],
+        sup: &[(String, ElmType)],
+        exact: bool,
+    ) -> bool {
+        if exact && sub.len() != sup.len() {
+            return false;
+        }
+        sub.iter().all(
+            |(name, t1)| match sup.iter().find(|(other, _)| other == name) {
+                Some((_, t2)) => self.unify(t1, t2),
+                None => false,
+            },
+        )
+    }
+}
@@ -1,10 +1,16 @@
-use crate::collections::FnCache;
+use crate::collections::{FnCache, NameIndex};
 use fn_search_backend_db::diesel::{pg::PgConnection, result::QueryResult};
 
 pub mod functions;
+pub mod repositories;
+pub mod types;
 
-use crate::queries::functions::get_all_func_sigs;
+use crate::queries::functions::{get_all_func_names, get_all_func_sigs};
 
 pub fn make_fn_cache(conn: &PgConnection) -> QueryResult<FnCache> {
     Ok(get_all_func_sigs(conn)?.into_iter().collect())
 }
+
+pub fn make_name_index(conn: &PgConnection) -> QueryResult<NameIndex> {
+    Ok(get_all_func_names(conn)?.into_iter().collect())
+}
@@ -1,17 +1,113 @@
-use fn_search_backend_db::diesel::{pg::PgConnection, prelude::*, result::QueryResult};
-use fn_search_backend_db::models::FunctionWithRepo;
+use crate::collections::Page;
+use fn_search_backend_db::diesel::{
+    pg::PgConnection,
+    prelude::*,
+    result::QueryResult,
+    sql_query,
+    sql_types::{BigInt, Text},
+};
+use fn_search_backend_db::models::{Count, FunctionTextMatch, FunctionWithRepo};
 
+/// the type signature of every function along with its id, functions appear once for each
+/// distinct form of their signature, as written, with type names resolved and with type aliases
+/// expanded
 pub fn get_all_func_sigs(conn: &PgConnection) -> QueryResult<Vec<(String, i64)>> {
     use fn_search_backend_db::schema::functions::dsl::*;
-    Ok(functions
-        .select((type_signature, id))
-        .load::<(String, i64)>(conn)?)
+    let sigs = functions
+        .select((
+            type_signature,
+            resolved_type_signature,
+            expanded_type_signature,
+            id,
+        ))
+        .load::<(String, String, String, i64)>(conn)?;
+    Ok(sigs
+        .into_iter()
+        .flat_map(|(written, resolved, expanded, func_id)| {
+            let mut sigs = vec![written];
+            for sig in [resolved, expanded].iter() {
+                if !sig.is_empty() && !sigs.contains(sig) {
+                    sigs.push(sig.clone());
+                }
+            }
+            sigs.into_iter().map(move |sig| (sig, func_id))
+        })
+        .collect())
+}
+
+/// the module and name of every function along with its id
+pub fn get_all_func_names(conn: &PgConnection) -> QueryResult<Vec<(String, String, i64)>> {
+    use fn_search_backend_db::schema::functions::dsl::*;
+    functions
+        .select((module, name, id))
+        .load::<(String, String, i64)>(conn)
 }
 
 pub fn get_functions(conn: &PgConnection, ids: &[i64]) -> QueryResult<Vec<FunctionWithRepo>> {
     use fn_search_backend_db::schema::repository_function_mat_view::dsl::*;
-    let fns = repository_function_mat_view
+    let mut fns = repository_function_mat_view
         .filter(func_id.eq_any(ids))
         .load::<FunctionWithRepo>(conn)?;
+    // results are returned in the same order as ids, since search results may be ranked
+    fns.sort_by_key(|f| ids.iter().position(|i| *i == f.func_id));
     Ok(fns)
 }
+
+pub fn get_function_ids_in_repos(conn: &PgConnection, repo_ids: &[i32]) -> QueryResult<Vec<i64>> {
+    use fn_search_backend_db::schema::functions::dsl::*;
+    functions
+        .filter(repo_id.eq_any(repo_ids))
+        .select(id)
+        .load::<i64>(conn)
+}
+
+pub fn get_function_ids_in_module(conn: &PgConnection, module_name: &str) -> QueryResult<Vec<i64>> {
+    use fn_search_backend_db::schema::functions::dsl::*;
+    functions
+        .filter(module.eq(module_name))
+        .select(id)
+        .load::<i64>(conn)
+}
+
+/// the document searched by full text search, it must be the same expression as in
+/// repo_func_mat_view_text_search_index
+const TEXT_SEARCH_DOCUMENT: &str = "(setweight(to_tsvector('english', v.func_name), 'A') || \
+     setweight(to_tsvector('english', coalesce(v.func_documentation, '')), 'B'))";
+
+/// returns at most limit functions whose name or documentation matches text, starting at offset,
+/// best matches first, along with the number of matching functions
+///
+/// names are weighted above documentation, snippets highlight matching words with `<b></b>`
+pub fn search_function_text(
+    conn: &PgConnection,
+    text: &str,
+    limit: i64,
+    offset: i64,
+) -> QueryResult<Page<FunctionTextMatch>> {
+    let items = sql_query(format!(
+        "SELECT v.*,
+                ts_rank({document}, query) AS rank,
+                ts_headline('english', coalesce(v.func_documentation, ''), query,
+                            'MaxFragments=2, MinWords=5, MaxWords=20') AS snippet
+         FROM repository_function_mat_view AS v, plainto_tsquery('english', $1) AS query
+         WHERE {document} @@ query
+         ORDER BY rank DESC, v.func_id
+         LIMIT $2 OFFSET $3",
+        document = TEXT_SEARCH_DOCUMENT
+    ))
+    .bind::<Text, _>(text)
+    .bind::<BigInt, _>(limit)
+    .bind::<BigInt, _>(offset)
+    .load::<FunctionTextMatch>(conn)?;
+    // counted separately, a page past the last one has no rows to count
+    let total = sql_query(format!(
+        "SELECT count(*) AS count
+         FROM repository_function_mat_view AS v, plainto_tsquery('english', $1) AS query
+         WHERE {document} @@ query",
+        document = TEXT_SEARCH_DOCUMENT
+    ))
+    .bind::<Text, _>(text)
+    .get_result::<Count>(conn)?
+    .count as usize;
+    Ok(Page { items, total })
+}
@@ -0,0 +1,7 @@
+use fn_search_backend_db::diesel::{pg::PgConnection, prelude::*, result::QueryResult};
+use fn_search_backend_db::models::Repository;
+
+pub fn get_repositories(conn: &PgConnection) -> QueryResult<Vec<Repository>> {
+    use fn_search_backend_db::schema::repositories::dsl::*;
+    repositories.load::<Repository>(conn)
+}
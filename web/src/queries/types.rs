@@ -0,0 +1,22 @@
+use fn_search_backend_db::diesel::{pg::PgConnection, prelude::*, result::QueryResult};
+use fn_search_backend_db::models::{Repository, Type};
+use fn_search_backend_db::schema::{repositories, types};
+
+/// types called type_name along with the package they are defined in, sorted by package,
+/// only types defined in module_name are returned if it is given
+pub fn get_types_by_name(
+    conn: &PgConnection,
+    type_name: &str,
+    module_name: Option<&str>,
+) -> QueryResult<Vec<(Type, Repository)>> {
+    let mut query = types::table
+        .inner_join(repositories::table)
+        .filter(types::name.eq(type_name))
+        .into_boxed();
+    if let Some(module_name) = module_name {
+        query = query.filter(types::module.eq(module_name));
+    }
+    query
+        .order((repositories::name, repositories::ver, types::module))
+        .load::<(Type, Repository)>(conn)
+}
@@ -0,0 +1,181 @@
+use fn_search_backend_db::models::{FunctionTextMatch, FunctionWithRepo, Repository, Type};
+use fn_search_backend_parsers::{parse_type_signature, ElmType};
+use serde_derive::Serialize;
+
+/// version of the response schema served under `/v1`, and without a version prefix
+pub const API_VERSION: u32 = 1;
+
+/// a page of results, along with the total number of results and the offset of the next page
+#[derive(Serialize, Debug, PartialEq)]
+pub struct PageResponse<T> {
+    pub api_version: u32,
+    pub data: Vec<T>,
+    pub total: usize,
+    pub next: Option<usize>,
+}
+
+impl<T> PageResponse<T> {
+    pub fn new(data: Vec<T>, total: usize, offset: usize) -> Self {
+        let end = offset + data.len();
+        PageResponse {
+            api_version: API_VERSION,
+            next: if !data.is_empty() && end < total {
+                Some(end)
+            } else {
+                None
+            },
+            data,
+            total,
+        }
+    }
+}
+
+/// the package a function is in
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RepoResponse {
+    pub id: i32,
+    pub name: String,
+    pub url: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FunctionResponse {
+    pub id: i64,
+    /// the name of the function qualified by its module, e.g. `Dict.get`
+    pub name: String,
+    /// the module the function is defined in, e.g. `Dict`
+    pub module: String,
+    pub type_signature: String,
+    /// the documentation of the function
+    pub desc: String,
+    /// the arguments of the function followed by its return type,
+    /// `(a -> b) -> List a -> List b` has args `["(a -> b)", "List a", "List b"]`
+    pub args: Vec<String>,
+    /// the distance of the type signature from the query for approximate searches,
+    /// lower scores are closer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u32>,
+}
+
+/// a function along with the package it is in, as decoded by the elm frontend
+#[derive(Serialize, Debug, PartialEq)]
+pub struct SearchResult {
+    pub repo: RepoResponse,
+    pub res: FunctionResponse,
+}
+
+impl SearchResult {
+    pub fn new(f: FunctionWithRepo, score: Option<u32>) -> Self {
+        let name = f.qualified_name();
+        SearchResult {
+            repo: RepoResponse {
+                id: f.repo_id,
+                name: f.repo_name,
+                url: f.repo_url,
+                version: f.repo_version,
+            },
+            res: FunctionResponse {
+                id: f.func_id,
+                name,
+                module: f.func_module,
+                args: signature_args(f.func_type_sig.as_str()),
+                type_signature: f.func_type_sig,
+                desc: f.func_documentation.unwrap_or_default(),
+                score,
+            },
+        }
+    }
+}
+
+/// a function matching a full text search
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TextSearchResult {
+    pub repo: RepoResponse,
+    pub res: FunctionResponse,
+    /// fragments of the documentation with matching words wrapped in `<b></b>`
+    pub snippet: String,
+    /// how well the function matches, higher ranks are better matches
+    pub rank: f32,
+}
+
+impl TextSearchResult {
+    pub fn new(m: FunctionTextMatch) -> Self {
+        let SearchResult { repo, res } = SearchResult::new(m.function, None);
+        TextSearchResult {
+            repo,
+            res,
+            snippet: m.snippet,
+            rank: m.rank,
+        }
+    }
+}
+
+/// a custom type or type alias
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TypeResponse {
+    pub id: i64,
+    /// the name of the type qualified by its module, e.g. `Dict.Dict`
+    pub name: String,
+    pub module: String,
+    /// `alias` or `custom`
+    pub kind: String,
+    pub parameters: Vec<String>,
+    /// the aliased type, or the constructors separated by `|`
+    pub definition: String,
+    pub constructors: Vec<String>,
+    /// the documentation of the type
+    pub desc: String,
+}
+
+/// a type along with the package it is in
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TypeResult {
+    pub repo: RepoResponse,
+    pub res: TypeResponse,
+}
+
+impl TypeResult {
+    pub fn new(t: Type, r: Repository) -> Self {
+        let name = if t.module.is_empty() {
+            t.name
+        } else {
+            format!("{}.{}", t.module, t.name)
+        };
+        TypeResult {
+            repo: RepoResponse {
+                id: r.id,
+                name: r.name,
+                url: r.url,
+                version: r.ver,
+            },
+            res: TypeResponse {
+                id: t.id,
+                name,
+                module: t.module,
+                kind: t.kind,
+                parameters: t.parameters,
+                definition: t.definition,
+                constructors: t.constructors,
+                desc: t.documentation.unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// splits a type signature into its arguments and return type,
+/// signatures which can't be parsed are returned whole
+pub fn signature_args(sig: &str) -> Vec<String> {
+    match parse_type_signature(sig) {
+        Some(ElmType::Function { args, ret }) => args
+            .iter()
+            .map(|arg| match arg {
+                ElmType::Function { .. } => format!("({})", arg),
+                _ => arg.to_string(),
+            })
+            .chain(Some(ret.to_string()))
+            .collect(),
+        Some(t) => vec![t.to_string()],
+        None => vec![sig.to_string()],
+    }
+}
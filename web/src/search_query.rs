@@ -0,0 +1,223 @@
+use crate::version::{Version, VersionConstraint};
+use std::error::Error;
+use std::fmt;
+
+/// names of the filters which can be given in a query, as `filter:value`
+const FILTERS: [&str; 5] = ["name", "package", "author", "version", "module"];
+
+/// A parsed search query, such as
+/// `map : (a -> b) -> List a -> List b package:elm/core module:List`
+///
+/// a query is an optional function name followed by ` : ` and a type signature, or just a type
+/// signature, along with any number of `filter:value` filters
+#[derive(Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    /// the name of the function, given before ` : ` or with `name:`
+    pub name: Option<String>,
+    pub type_signature: Option<String>,
+    /// the name of the package the function is in, e.g. `elm/core`, `*` matches any characters
+    /// so `elm/*` matches every package by elm
+    pub package: Option<String>,
+    /// the author of the package the function is in, e.g. `elm-community`
+    pub author: Option<String>,
+    /// a constraint on the version of the package the function is in, e.g. `>=2.0.0`
+    pub version: Option<VersionConstraint>,
+    /// the name of the module the function is in, e.g. `List`
+    pub module: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum QueryError {
+    /// the query has no function name or type signature to search for
+    Empty,
+    /// a filter which doesn't exist, such as `pkg:elm/core`
+    UnknownFilter(String),
+    /// a filter without a value, such as `package:`
+    MissingValue(String),
+    /// a filter which was given more than once
+    DuplicateFilter(String),
+    /// a filter with a value which can't be parsed, such as `version:>=two`
+    InvalidValue(String, String),
+}
+
+impl Error for QueryError {}
+
+impl fmt::Display for QueryError {
+    fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> Result<(), fmt::Error> {
+        match self {
+            QueryError::Empty => write!(
+                f,
+                "query must contain a function name or a type signature to search for"
+            ),
+            QueryError::UnknownFilter(filter) => write!(
+                f,
+                "unknown filter `{}:`, expected one of {}. \
+                 separate a function name from its type signature with ` : `",
+                filter,
+                FILTERS
+                    .iter()
+                    .map(|f| format!("`{}:`", f))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            QueryError::MissingValue(filter) => {
+                write!(f, "filter `{}:` must be followed by a value", filter)
+            }
+            QueryError::DuplicateFilter(filter) => {
+                write!(f, "filter `{}:` may only be given once", filter)
+            }
+            QueryError::InvalidValue(filter, value) => {
+                write!(f, "invalid value `{}` for filter `{}:`", value, filter)
+            }
+        }
+    }
+}
+
+/// splits a query into words separated by whitespace, whitespace inside of parentheses or
+/// braces does not split words, so `(a -> b)` and `{ x : Int }` are a single word
+fn words(query: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut depth: usize = 0;
+    let mut start = None;
+    for (i, c) in query.char_indices() {
+        match c {
+            '(' | '{' => depth += 1,
+            ')' | '}' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    words.push(&query[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        words.push(&query[s..]);
+    }
+    words
+}
+
+/// splits a word of the form `filter:value` into the filter and its value
+fn filter(word: &str) -> Option<(&str, &str)> {
+    let colon = word.find(':')?;
+    let (filter, value) = (&word[..colon], &word[colon + 1..]);
+    if !filter.is_empty() && filter.chars().all(|c| c.is_ascii_lowercase()) {
+        Some((filter, value))
+    } else {
+        None
+    }
+}
+
+fn set_filter<T>(field: &mut Option<T>, filter: &str, value: T) -> Result<(), QueryError> {
+    if field.is_some() {
+        return Err(QueryError::DuplicateFilter(filter.to_string()));
+    }
+    *field = Some(value);
+    Ok(())
+}
+
+/// true if text matches pattern, where `*` in pattern matches any number of characters
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match text.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let last = match parts.pop() {
+        Some(last) => last,
+        // no wildcards
+        None => return rest.is_empty(),
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+impl SearchQuery {
+    /// add a filter to the query, returns an error if the filter doesn't exist, has already been
+    /// given, or its value is invalid
+    pub fn add_filter(&mut self, filter: &str, value: &str) -> Result<(), QueryError> {
+        if !FILTERS.contains(&filter) {
+            return Err(QueryError::UnknownFilter(filter.to_string()));
+        }
+        if value.is_empty() {
+            return Err(QueryError::MissingValue(filter.to_string()));
+        }
+        let value_string = value.to_string();
+        match filter {
+            "name" => set_filter(&mut self.name, filter, value_string),
+            "package" => set_filter(&mut self.package, filter, value_string),
+            "author" => set_filter(&mut self.author, filter, value_string),
+            "version" => {
+                let constraint = value
+                    .parse()
+                    .map_err(|_| QueryError::InvalidValue(filter.to_string(), value.to_string()))?;
+                set_filter(&mut self.version, filter, constraint)
+            }
+            "module" => set_filter(&mut self.module, filter, value_string),
+            _ => Err(QueryError::UnknownFilter(filter.to_string())),
+        }
+    }
+
+    /// true if the query restricts which packages functions may be from
+    pub fn has_package_filters(&self) -> bool {
+        self.package.is_some() || self.author.is_some() || self.version.is_some()
+    }
+
+    /// true if functions from the package called name at version are allowed by the query
+    pub fn matches_package(&self, name: &str, version: &str) -> bool {
+        if let Some(pattern) = &self.package {
+            if !glob_match(pattern, name) {
+                return false;
+            }
+        }
+        if let Some(author) = &self.author {
+            if name.split('/').next() != Some(author.as_str()) {
+                return false;
+            }
+        }
+        if let Some(constraint) = &self.version {
+            match version.parse::<Version>() {
+                Ok(v) if constraint.matches(&v) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+/// parse a search query, see [SearchQuery](struct.SearchQuery.html)
+pub fn parse_query(query: &str) -> Result<SearchQuery, QueryError> {
+    let mut res = SearchQuery::default();
+    let mut terms = Vec::new();
+    for word in words(query) {
+        match filter(word) {
+            Some((filter, value)) => res.add_filter(filter, value)?,
+            None => terms.push(word),
+        }
+    }
+    let (name, type_signature) = match terms.iter().position(|w| *w == ":") {
+        Some(i) => (&terms[..i], &terms[i + 1..]),
+        None => (&terms[..0], &terms[..]),
+    };
+    if !name.is_empty() {
+        set_filter(&mut res.name, "name", name.join(" "))?;
+    }
+    if !type_signature.is_empty() {
+        res.type_signature = Some(type_signature.join(" "));
+    }
+    if res.name.is_none() && res.type_signature.is_none() {
+        return Err(QueryError::Empty);
+    }
+    Ok(res)
+}
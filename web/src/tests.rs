@@ -1,2 +1,12 @@
 #[cfg(test)]
 mod collections;
+#[cfg(test)]
+mod matching;
+#[cfg(test)]
+mod queries;
+#[cfg(test)]
+mod responses;
+#[cfg(test)]
+mod search_query;
+#[cfg(test)]
+mod version;
@@ -1,5 +1,6 @@
-use crate::collections::FnCache;
+use crate::collections::{FnCache, Page};
 use fn_search_backend_db::models::Function;
+use lazy_static::lazy_static;
 use std::collections::HashSet;
 
 #[test]
@@ -21,36 +22,78 @@ lazy_static! {
             repo_id: 0,
             name: String::from("derpyfn"),
             type_signature: String::from("Int -> Int"),
+            documentation: None,
+            module: String::new(),
+            expanded_type_signature: String::new(),
+            resolved_type_signature: String::new(),
+            associativity: None,
+            precedence: None,
+            port: false,
         },
         Function {
             id: 1,
             repo_id: 0,
             name: String::from("whatever"),
             type_signature: String::from("String -> Int"),
+            documentation: None,
+            module: String::new(),
+            expanded_type_signature: String::new(),
+            resolved_type_signature: String::new(),
+            associativity: None,
+            precedence: None,
+            port: false,
         },
         Function {
             id: 2,
             repo_id: 1,
             name: String::from("lol"),
             type_signature: String::from("Int -> Bool"),
+            documentation: None,
+            module: String::new(),
+            expanded_type_signature: String::new(),
+            resolved_type_signature: String::new(),
+            associativity: None,
+            precedence: None,
+            port: false,
         },
         Function {
             id: 3,
             repo_id: 1,
             name: String::from("zxc"),
             type_signature: String::from("Bool -> Bool"),
+            documentation: None,
+            module: String::new(),
+            expanded_type_signature: String::new(),
+            resolved_type_signature: String::new(),
+            associativity: None,
+            precedence: None,
+            port: false,
         },
         Function {
             id: 4,
             repo_id: 1,
             name: String::from("fef"),
             type_signature: String::from("Int -> String"),
+            documentation: None,
+            module: String::new(),
+            expanded_type_signature: String::new(),
+            resolved_type_signature: String::new(),
+            associativity: None,
+            precedence: None,
+            port: false,
         },
         Function {
             id: 5,
             repo_id: 0,
             name: String::from("rer"),
             type_signature: String::from("String -> Int"),
+            documentation: None,
+            module: String::new(),
+            expanded_type_signature: String::new(),
+            resolved_type_signature: String::new(),
+            associativity: None,
+            precedence: None,
+            port: false,
         },
     ];
 }
@@ -75,7 +118,7 @@ fn search_gives_values() {
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 2);
-    let res: HashSet<i64> = res.into_iter().map(|v| *v).collect();
+    let res: HashSet<i64> = res.iter().copied().collect();
     assert_eq!(res, vec![1, 5].into_iter().collect());
 }
 
@@ -86,7 +129,7 @@ fn search_gives_value() {
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 1);
-    let res: HashSet<i64> = res.into_iter().map(|v| *v).collect();
+    let res: HashSet<i64> = res.iter().copied().collect();
     assert_eq!(res, vec![3].into_iter().collect());
 }
 
@@ -104,7 +147,7 @@ fn search_gives_some_start_index() {
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 1);
-    let res: HashSet<i64> = res.into_iter().map(|v| *v).collect();
+    let res: HashSet<i64> = res.iter().copied().collect();
     assert_eq!(res, vec![5].into_iter().collect());
 }
 
@@ -115,7 +158,7 @@ fn search_gives_only_num() {
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 1);
-    let res: HashSet<i64> = res.into_iter().map(|v| *v).collect();
+    let res: HashSet<i64> = res.iter().copied().collect();
     assert_eq!(res, vec![1].into_iter().collect());
 }
 
@@ -126,7 +169,7 @@ fn search_gives_only_num_with_start() {
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 1);
-    let res: HashSet<i64> = res.into_iter().map(|v| *v).collect();
+    let res: HashSet<i64> = res.iter().copied().collect();
     assert_eq!(res, vec![5].into_iter().collect());
 }
 
@@ -140,7 +183,7 @@ fn search_gives_none_on_bad_key() {
 #[test]
 fn suggest_gives_suggestion() {
     let c = setup_test_cache();
-    let res = c.suggest("String -> In", 10);
+    let res = c.suggest("String -> In", 10, None);
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 1);
@@ -150,7 +193,7 @@ fn suggest_gives_suggestion() {
 #[test]
 fn suggest_gives_suggestions() {
     let c = setup_test_cache();
-    let res = c.suggest("In", 10);
+    let res = c.suggest("In", 10, None);
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 3);
@@ -165,15 +208,256 @@ fn suggest_gives_suggestions() {
 #[test]
 fn suggest_gives_x_suggestions() {
     let c = setup_test_cache();
-    let res = c.suggest("In", 2);
+    let res = c.suggest("In", 2, None);
     assert!(res.is_some());
     let res = res.unwrap();
     assert_eq!(res.len(), 2);
 }
 
+#[test]
+fn suggest_normalizes_prefix() {
+    let c: FnCache = vec![("a -> List a", 0), ("b -> a -> b", 1)]
+        .into_iter()
+        .collect();
+    assert_eq!(c.suggest("x -> List", 10, None), Some(vec!["a -> List a"]));
+    assert_eq!(
+        c.suggest("x  ->  List", 10, None),
+        Some(vec!["a -> List a"])
+    );
+    assert_eq!(c.suggest("x -> y ->", 10, None), Some(vec!["a -> b -> a"]));
+    assert_eq!(c.suggest_count("item -> List"), 1);
+}
+
 #[test]
 fn suggest_gives_none() {
     let c = setup_test_cache();
-    let res = c.suggest("Ink", 10);
+    let res = c.suggest("Ink", 10, None);
+    assert!(res.is_none());
+}
+
+#[test]
+fn search_ignores_type_variable_names() {
+    let c: FnCache = vec![("a -> List a", 0), ("b -> b", 1), ("a -> b -> a", 2)]
+        .into_iter()
+        .collect();
+    let res = c.search("x -> List x", 10, None);
+    assert_eq!(res, Some(&[0][..]));
+    let res = c.search("a -> a", 10, None);
+    assert_eq!(res, Some(&[1][..]));
+    let res = c.search("first -> second -> first", 10, None);
+    assert_eq!(res, Some(&[2][..]));
+    let res = c.search("a -> b -> b", 10, None);
     assert!(res.is_none());
 }
+
+#[test]
+fn search_ignores_whitespace_and_parentheses() {
+    let c: FnCache = vec![("(a -> b) -> List a -> List b", 0)]
+        .into_iter()
+        .collect();
+    let res = c.search("( x->y )  ->  List (x) -> List y", 10, None);
+    assert_eq!(res, Some(&[0][..]));
+}
+
+#[test]
+fn search_unifiable_ranks_exact_matches_first() {
+    let c: FnCache = vec![
+        ("List a -> Int", 0),
+        ("List String -> Int", 1),
+        ("List String -> String", 2),
+        ("a -> Int", 3),
+    ]
+    .into_iter()
+    .collect();
+    let res = c.search_unifiable("List String -> Int", 10, None);
+    assert_eq!(
+        res,
+        Some(Page {
+            items: vec![1, 0, 3],
+            total: 3
+        })
+    );
+    let res = c.search_unifiable("List String -> Int", 1, Some(1));
+    assert_eq!(
+        res,
+        Some(Page {
+            items: vec![0],
+            total: 3
+        })
+    );
+    let res = c.search_unifiable("List b -> Int", 10, None);
+    assert_eq!(res.map(|p| p.items), Some(vec![0, 3, 1]));
+}
+
+#[test]
+fn search_unifiable_gives_none() {
+    let c = setup_test_cache();
+    assert!(c.search_unifiable("Float -> Float", 10, None).is_none());
+    assert!(c.search_unifiable("Int ->", 10, None).is_none());
+}
+
+#[test]
+fn search_unifiable_gives_empty_page_on_invalid_start() {
+    let c = setup_test_cache();
+    let res = c.search_unifiable("Int -> Int", 10, Some(1));
+    assert_eq!(
+        res,
+        Some(Page {
+            items: vec![],
+            total: 1
+        })
+    );
+}
+
+#[test]
+fn search_any_order_finds_reordered_arguments() {
+    let c: FnCache = vec![
+        ("comparable -> Dict comparable v -> Maybe v", 0),
+        ("Dict comparable v -> comparable -> Maybe v", 1),
+        ("comparable -> Dict comparable v -> Bool", 2),
+    ]
+    .into_iter()
+    .collect();
+    let res = c.search_any_order("Dict k v -> k -> Maybe v", 10, None);
+    assert_eq!(res.map(|p| p.items), Some(vec![1, 0]));
+    let res = c.search_any_order("k -> Dict k v -> Maybe v", 10, None);
+    assert_eq!(res.map(|p| p.items), Some(vec![0, 1]));
+    let res = c.search_any_order("Dict k v -> k -> Maybe v", 1, Some(1));
+    assert_eq!(
+        res,
+        Some(Page {
+            items: vec![0],
+            total: 2
+        })
+    );
+}
+
+#[test]
+fn search_approximate_ranks_by_distance() {
+    let c: FnCache = vec![
+        ("String -> Maybe Int", 0),
+        ("String -> Int", 1),
+        ("Bool -> Bool -> Bool", 2),
+        ("a -> Int", 3),
+    ]
+    .into_iter()
+    .collect();
+    let res = c.search_approximate("String -> Int", 10, None);
+    assert!(res.is_some());
+    let res = res.unwrap();
+    assert_eq!(res.total, 3);
+    let ids: Vec<i64> = res.items.iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids, vec![1, 3, 0]);
+    assert_eq!(res.items[0].1, 0);
+    assert!(res.items[1].1 <= res.items[2].1);
+    let res = c.search_approximate("String -> Int", 1, Some(1));
+    assert_eq!(res.map(|p| p.items[0].0), Some(3));
+}
+
+#[test]
+fn count_gives_total() {
+    let c = setup_test_cache();
+    assert_eq!(c.count("String -> Int"), 2);
+    assert_eq!(c.count("Bool -> Bool"), 1);
+    assert_eq!(c.count("String -> In"), 0);
+}
+
+#[test]
+fn suggest_gives_some_start_index() {
+    let c = setup_test_cache();
+    let all = c.suggest("In", 10, None).unwrap();
+    let res = c.suggest("In", 10, Some(1));
+    assert_eq!(res, Some(all[1..].to_vec()));
+    assert!(c.suggest("In", 10, Some(3)).is_none());
+}
+
+#[test]
+fn suggest_count_gives_total() {
+    let c = setup_test_cache();
+    assert_eq!(c.suggest_count("In"), 3);
+    assert_eq!(c.suggest_count("String -> In"), 1);
+    assert_eq!(c.suggest_count("Ink"), 0);
+}
+
+#[test]
+fn search_matches_every_signature_form() {
+    let f = Function {
+        id: 7,
+        repo_id: 0,
+        name: String::from("distance"),
+        type_signature: String::from("Point -> Point -> Float"),
+        documentation: None,
+        module: String::new(),
+        expanded_type_signature: String::from(
+            "{ x : Float, y : Float } -> { x : Float, y : Float } -> Float",
+        ),
+        resolved_type_signature: String::from("Geometry.Point -> Geometry.Point -> Float"),
+        associativity: None,
+        precedence: None,
+        port: false,
+    };
+    let c: FnCache = vec![&f].into_iter().collect();
+    assert_eq!(
+        c.search("Point -> Point -> Float", 10, None),
+        Some(&[7][..])
+    );
+    assert_eq!(
+        c.search(
+            "{ x : Float, y : Float } -> { x : Float, y : Float } -> Float",
+            10,
+            None
+        ),
+        Some(&[7][..])
+    );
+    assert_eq!(
+        c.search("Geometry.Point -> Geometry.Point -> Float", 10, None),
+        Some(&[7][..])
+    );
+}
+
+#[test]
+fn search_gives_each_function_once_per_normalized_signature() {
+    let f = Function {
+        id: 3,
+        repo_id: 0,
+        name: String::from("identity"),
+        type_signature: String::from("a -> a"),
+        documentation: None,
+        module: String::new(),
+        expanded_type_signature: String::from("(b) ->  b"),
+        resolved_type_signature: String::from("value -> value"),
+        associativity: None,
+        precedence: None,
+        port: false,
+    };
+    let c: FnCache = vec![&f].into_iter().collect();
+    assert_eq!(c.search("x -> x", 10, None), Some(&[3][..]));
+    assert_eq!(c.count("x -> x"), 1);
+}
+
+#[test]
+fn search_record_fields_finds_records_with_additional_fields() {
+    let c: FnCache = vec![
+        ("{ name : String, age : Int } -> String", 0),
+        ("{ name : String } -> String", 1),
+        ("{ a | name : String } -> String", 2),
+        (
+            "{ age : Int, nickname : String, name : String } -> String",
+            3,
+        ),
+        ("{ age : Int } -> String", 4),
+    ]
+    .into_iter()
+    .collect();
+    let res = c.search_record_fields("{ name : String } -> String", 10, None);
+    assert_eq!(
+        res,
+        Some(Page {
+            items: vec![1, 0, 3],
+            total: 3
+        })
+    );
+    assert!(c
+        .search_record_fields("String -> String", 10, None)
+        .is_none());
+}
@@ -1,2 +1,4 @@
 #[cfg(test)]
 mod fn_cache;
+#[cfg(test)]
+mod name_index;
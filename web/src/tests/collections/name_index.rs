@@ -0,0 +1,145 @@
+use crate::collections::{NameIndex, NameMatch, Page};
+
+fn test_index() -> NameIndex {
+    vec![
+        ("foldl", 0),
+        ("foldr", 1),
+        ("map", 2),
+        ("foldl", 3),
+        ("indexedMap", 4),
+        ("filterMap", 5),
+        ("mapAccuml", 6),
+        ("fold", 7),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn search_exact() {
+    let index = test_index();
+    assert_eq!(
+        index.search("foldl", NameMatch::Exact, 10, None),
+        Page {
+            items: vec![0, 3],
+            total: 2,
+        }
+    );
+    assert_eq!(
+        index.search("fold", NameMatch::Exact, 10, None),
+        Page {
+            items: vec![7],
+            total: 1,
+        }
+    );
+    assert_eq!(
+        index.search("Foldl", NameMatch::Exact, 10, None),
+        Page {
+            items: Vec::new(),
+            total: 0,
+        }
+    );
+}
+
+#[test]
+fn search_prefix_puts_exact_matches_first() {
+    let index = test_index();
+    assert_eq!(
+        index.search("fold", NameMatch::Prefix, 10, None),
+        Page {
+            items: vec![7, 0, 3, 1],
+            total: 4,
+        }
+    );
+    assert_eq!(
+        index.search("map", NameMatch::Prefix, 10, None),
+        Page {
+            items: vec![2, 6],
+            total: 2,
+        }
+    );
+}
+
+#[test]
+fn search_substring_ignores_case() {
+    let index = test_index();
+    assert_eq!(
+        index.search("map", NameMatch::Substring, 10, None),
+        Page {
+            items: vec![2, 6, 5, 4],
+            total: 4,
+        }
+    );
+    assert_eq!(
+        index.search("MAP", NameMatch::Substring, 10, None),
+        Page {
+            items: vec![5, 4, 2, 6],
+            total: 4,
+        }
+    );
+}
+
+#[test]
+fn search_pages() {
+    let index = test_index();
+    assert_eq!(
+        index.search("map", NameMatch::Substring, 2, Some(1)),
+        Page {
+            items: vec![6, 5],
+            total: 4,
+        }
+    );
+    assert_eq!(
+        index.search("map", NameMatch::Substring, 2, Some(4)),
+        Page {
+            items: vec![],
+            total: 4,
+        }
+    );
+}
+
+#[test]
+fn search_gives_empty_page() {
+    let index = test_index();
+    assert_eq!(
+        index.search("nothing", NameMatch::Substring, 10, None),
+        Page {
+            items: Vec::new(),
+            total: 0,
+        }
+    );
+}
+
+#[test]
+fn search_qualified_names() {
+    let index: NameIndex = vec![
+        (String::from("List"), String::from("foldl"), 0),
+        (String::from("Array"), String::from("foldl"), 1),
+        (String::from("List"), String::from("map"), 2),
+        (String::new(), String::from("foldr"), 3),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        index.search("List.foldl", NameMatch::Exact, 10, None),
+        Page {
+            items: vec![0],
+            total: 1,
+        }
+    );
+    assert_eq!(
+        index.search("List.", NameMatch::Prefix, 10, None),
+        Page {
+            items: vec![0, 2],
+            total: 2,
+        }
+    );
+    // functions matching by both their name and qualified name are only found once
+    assert_eq!(
+        index.search("fold", NameMatch::Substring, 10, None),
+        Page {
+            items: vec![0, 1, 3],
+            total: 3,
+        }
+    );
+}
@@ -0,0 +1,53 @@
+use crate::matching::type_distance;
+use fn_search_backend_parsers::{parse_type_signature, ElmType};
+
+fn parse(sig: &str) -> ElmType {
+    parse_type_signature(sig).expect("error parsing type signature")
+}
+
+fn distance(query: &str, stored: &str) -> u32 {
+    type_distance(&parse(query), &parse(stored))
+}
+
+#[test]
+fn same_signature_has_no_distance() {
+    assert_eq!(distance("List a -> Int", "List b -> Int"), 0);
+    assert_eq!(distance("{ x : Float } -> ()", "{ x : Float } -> ()"), 0);
+}
+
+#[test]
+fn general_signatures_are_closer_than_specific_ones() {
+    let general = distance("List String -> Int", "List a -> Int");
+    let specific = distance("List a -> Int", "List String -> Int");
+    assert!(general > 0);
+    assert!(general < specific);
+}
+
+#[test]
+fn missing_arguments_add_distance() {
+    let missing = distance("Int -> String -> Bool", "Int -> Bool");
+    let extra = distance("Int -> Bool", "Int -> String -> Bool");
+    assert!(missing > 0);
+    assert_eq!(missing, extra);
+    assert!(distance("Int -> String -> Bool", "String -> Bool") < missing + missing);
+}
+
+#[test]
+fn wrapped_types_are_close() {
+    let maybe = distance("String -> Int", "String -> Maybe Int");
+    let result = distance("String -> Result String Int", "String -> Int");
+    let other = distance("String -> Int", "String -> Bool");
+    assert!(maybe > 0);
+    assert!(maybe < other);
+    assert!(result < other);
+}
+
+#[test]
+fn qualified_names_are_close() {
+    assert_eq!(distance("Dict String Int", "Dict.Dict String Int"), 0);
+}
+
+#[test]
+fn different_constructors_are_far() {
+    assert!(distance("Int -> Int", "String -> String") > distance("Int -> Int", "Int -> String"));
+}
@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod distance;
+#[cfg(test)]
+mod permute;
+#[cfg(test)]
+mod records;
+#[cfg(test)]
+mod unify;
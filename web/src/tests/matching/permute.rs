@@ -0,0 +1,72 @@
+use crate::matching::{match_permuted, unify::TypeMatch};
+use fn_search_backend_parsers::{parse_type_signature, ElmType};
+
+fn parse(sig: &str) -> ElmType {
+    parse_type_signature(sig).expect("error parsing type signature")
+}
+
+fn check(query: &str, stored: &str) -> Option<(usize, TypeMatch)> {
+    match_permuted(&parse(query), &parse(stored))
+}
+
+#[test]
+fn matches_original_order() {
+    assert_eq!(
+        check(
+            "comparable -> Dict comparable v -> Maybe v",
+            "comparable -> Dict comparable v -> Maybe v"
+        ),
+        Some((0, TypeMatch::Exact))
+    );
+}
+
+#[test]
+fn matches_swapped_arguments() {
+    assert_eq!(
+        check(
+            "Dict k v -> k -> Maybe v",
+            "comparable -> Dict comparable v -> Maybe v"
+        ),
+        Some((1, TypeMatch::Instance))
+    );
+    assert_eq!(
+        check(
+            "List a -> (a -> Bool) -> List a",
+            "(a -> Bool) -> List a -> List a"
+        ),
+        Some((1, TypeMatch::Exact))
+    );
+}
+
+#[test]
+fn counts_adjacent_swaps() {
+    assert_eq!(
+        check("String -> Bool -> Int -> ()", "Int -> Bool -> String -> ()"),
+        Some((3, TypeMatch::Exact))
+    );
+    assert_eq!(
+        check("Bool -> String -> Int -> ()", "Int -> Bool -> String -> ()"),
+        Some((2, TypeMatch::Exact))
+    );
+}
+
+#[test]
+fn prefers_fewest_swaps() {
+    // reversing the arguments would be exact, but the original order already unifies
+    assert_eq!(
+        check("c -> b -> a -> ( a, b, c )", "a -> b -> c -> ( a, b, c )"),
+        Some((0, TypeMatch::Unifiable))
+    );
+    // both orderings unify, but keeping the order needs no swaps
+    assert_eq!(
+        check("Int -> a -> Int", "Int -> Int -> Int"),
+        Some((0, TypeMatch::Instance))
+    );
+}
+
+#[test]
+fn rejects_different_arguments() {
+    assert_eq!(check("Int -> String -> Bool", "Int -> Int -> Bool"), None);
+    assert_eq!(check("Int -> String -> Bool", "Int -> String -> Int"), None);
+    assert_eq!(check("Int -> String -> Bool", "String -> Bool"), None);
+}
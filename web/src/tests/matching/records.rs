@@ -0,0 +1,94 @@
+use crate::matching::{has_records, match_record_fields};
+use fn_search_backend_parsers::{parse_type_signature, ElmType};
+
+fn parse(sig: &str) -> ElmType {
+    parse_type_signature(sig)
+        .expect("error parsing type signature")
+        .normalize()
+}
+
+fn check(query: &str, stored: &str) -> Option<usize> {
+    match_record_fields(&parse(query), &parse(stored))
+}
+
+#[test]
+fn counts_additional_fields() {
+    assert_eq!(
+        check("{ name : String } -> String", "{ name : String } -> String"),
+        Some(0)
+    );
+    assert_eq!(
+        check(
+            "{ name : String } -> String",
+            "{ name : String, age : Int } -> String"
+        ),
+        Some(1)
+    );
+    assert_eq!(
+        check(
+            "List { id : Int } -> { id : Int }",
+            "List { id : Int, x : Float } -> { id : Int, y : Float, z : Float }"
+        ),
+        Some(3)
+    );
+}
+
+#[test]
+fn ignores_field_order_and_variable_names() {
+    assert_eq!(
+        check(
+            "{ y : b, x : a } -> a",
+            "{ x : item, y : key, z : Int } -> item"
+        ),
+        Some(1)
+    );
+    assert_eq!(
+        check("{ x : a, y : a } -> a", "{ x : a, y : b } -> a"),
+        None
+    );
+    assert_eq!(check("{ x : a } -> comparable", "{ x : a } -> b"), None);
+}
+
+#[test]
+fn matches_extensible_records() {
+    assert_eq!(
+        check(
+            "{ r | name : String } -> r",
+            "{ a | name : String, age : Int } -> a"
+        ),
+        Some(1)
+    );
+    assert_eq!(
+        check(
+            "{ name : String } -> String",
+            "{ a | name : String } -> String"
+        ),
+        None
+    );
+}
+
+#[test]
+fn rejects_missing_fields() {
+    assert_eq!(
+        check(
+            "{ name : String } -> String",
+            "{ age : Int, nickname : String } -> String"
+        ),
+        None
+    );
+    assert_eq!(
+        check("{ name : String } -> String", "{ name : Int } -> String"),
+        None
+    );
+    assert_eq!(
+        check("{ name : String } -> String", "String -> String"),
+        None
+    );
+}
+
+#[test]
+fn finds_records() {
+    assert!(has_records(&parse("Maybe { x : Int } -> Int")));
+    assert!(has_records(&parse("{ a | x : Int } -> Int")));
+    assert!(!has_records(&parse("Maybe a -> (Int, String)")));
+}
@@ -0,0 +1,151 @@
+use crate::matching::{match_types, unify::TypeMatch};
+use fn_search_backend_parsers::{parse_type_signature, ElmType};
+
+fn parse(sig: &str) -> ElmType {
+    parse_type_signature(sig).expect("error parsing type signature")
+}
+
+fn check(query: &str, stored: &str) -> Option<TypeMatch> {
+    match_types(&parse(query), &parse(stored))
+}
+
+#[test]
+fn exact_match_ignores_variable_names() {
+    assert_eq!(check("a -> List a", "x -> List x"), Some(TypeMatch::Exact));
+    assert_eq!(
+        check("Int -> String", "Int -> String"),
+        Some(TypeMatch::Exact)
+    );
+}
+
+#[test]
+fn finds_more_general_signatures() {
+    assert_eq!(
+        check("List String -> Int", "List a -> Int"),
+        Some(TypeMatch::Generalization)
+    );
+    assert_eq!(
+        check(
+            "(Int -> String) -> List Int -> List String",
+            "(a -> b) -> List a -> List b"
+        ),
+        Some(TypeMatch::Generalization)
+    );
+}
+
+#[test]
+fn finds_more_specific_signatures() {
+    assert_eq!(
+        check("List a -> Int", "List String -> Int"),
+        Some(TypeMatch::Instance)
+    );
+}
+
+#[test]
+fn finds_unifiable_signatures() {
+    assert_eq!(
+        check("a -> Int -> a", "String -> b -> String"),
+        Some(TypeMatch::Unifiable)
+    );
+}
+
+#[test]
+fn rejects_mismatched_signatures() {
+    assert_eq!(check("List String -> Int", "List a -> String"), None);
+    assert_eq!(check("a -> a", "Int -> String"), None);
+    assert_eq!(check("Int -> Int", "Int -> Int -> Int"), None);
+    // occurs check
+    assert_eq!(check("a -> a", "List b -> b"), None);
+}
+
+#[test]
+fn unifies_curried_functions() {
+    assert_eq!(
+        check("a -> b -> c", "x -> y"),
+        Some(TypeMatch::Generalization)
+    );
+    assert_eq!(
+        check("Int -> (Int -> Int)", "Int -> Int -> Int"),
+        Some(TypeMatch::Exact)
+    );
+}
+
+#[test]
+fn respects_constrained_variables() {
+    assert_eq!(
+        check("Int -> Int -> Int", "number -> number -> number"),
+        Some(TypeMatch::Generalization)
+    );
+    assert_eq!(check("Bool -> Bool", "number -> number"), None);
+    assert_eq!(
+        check("a -> a", "comparable -> comparable"),
+        Some(TypeMatch::Instance)
+    );
+    assert_eq!(
+        check("List comparable -> List comparable", "List a -> List a"),
+        Some(TypeMatch::Generalization)
+    );
+}
+
+#[test]
+fn unifies_extensible_records() {
+    assert_eq!(
+        check(
+            "{ name : String, age : Int } -> String",
+            "{ a | name : String } -> String"
+        ),
+        Some(TypeMatch::Generalization)
+    );
+    assert_eq!(
+        check("{ age : Int } -> String", "{ a | name : String } -> String"),
+        None
+    );
+}
+
+#[test]
+fn repeated_extensible_record_bases_hold_the_same_fields() {
+    let query = "{ r | name : String } -> { r | name : String }";
+    let stored = "{ name : String, age : Int } -> { name : String }";
+    assert_eq!(check(query, stored), None);
+    assert_eq!(check(stored, query), None);
+    assert_eq!(
+        check(
+            query,
+            "{ name : String, age : Int } -> { name : String, age : Int }"
+        ),
+        Some(TypeMatch::Instance)
+    );
+}
+
+#[test]
+fn records_match_records_with_additional_fields() {
+    assert_eq!(
+        check(
+            "{ name : String } -> String",
+            "{ age : Int, name : String } -> String"
+        ),
+        Some(TypeMatch::Instance)
+    );
+    assert_eq!(
+        check(
+            "{ name : String } -> String",
+            "{ b | age : Int, name : String } -> String"
+        ),
+        Some(TypeMatch::Instance)
+    );
+    assert_eq!(
+        check(
+            "{ a | name : String } -> String",
+            "{ b | age : Int, name : String } -> String"
+        ),
+        Some(TypeMatch::Instance)
+    );
+    assert_eq!(
+        check("{ name : String } -> String", "{ name : Int } -> String"),
+        None
+    );
+    assert_eq!(
+        check("{ name : String } -> String", "{ age : Int } -> String"),
+        None
+    );
+}
@@ -0,0 +1,83 @@
+use crate::queries::functions::search_function_text;
+use fn_search_backend::get_config;
+use fn_search_backend_db::diesel::{self, pg::PgConnection, prelude::*, sql_query};
+use fn_search_backend_db::models::{NewFunction, NewRepository, Repository};
+use fn_search_backend_db::schema::{functions, repositories};
+use fn_search_backend_db::utils::get_db_url;
+
+static RELATIVE_CFG_FILE: &str = "../config.toml";
+
+fn connection() -> PgConnection {
+    let cfg = get_config(RELATIVE_CFG_FILE).expect("error finding configuration file");
+    let db_url = get_db_url(&cfg.db);
+    PgConnection::establish(db_url.as_str()).expect("error establishing connection to db")
+}
+
+fn refresh_mat_view(conn: &PgConnection) {
+    sql_query("REFRESH MATERIALIZED VIEW repository_function_mat_view")
+        .execute(conn)
+        .expect("error refreshing materialized view");
+}
+
+/// stores a repository with functions documented with `word`, replacing any stored earlier
+fn insert_documented_functions(conn: &PgConnection, repo_name: &str, word: &str, num: usize) {
+    delete_repo(conn, repo_name);
+    let repo = diesel::insert_into(repositories::table)
+        .values(&NewRepository {
+            name: repo_name,
+            url: "https://github.com/fn-search-test/text-search",
+            ver: "1.0.0",
+        })
+        .get_result::<Repository>(conn)
+        .expect("error inserting repo");
+    let names: Vec<String> = (0..num).map(|i| format!("documented{}", i)).collect();
+    let documentation = format!("returns a {} of the list", word);
+    let new_funcs: Vec<NewFunction> = names
+        .iter()
+        .map(|name| NewFunction {
+            repo_id: repo.id,
+            type_signature: String::from("List a -> a"),
+            name: name.as_str(),
+            documentation: Some(documentation.as_str()),
+            module: "TextSearch",
+            expanded_type_signature: String::from("List a -> a"),
+            resolved_type_signature: String::from("List a -> a"),
+            associativity: None,
+            precedence: None,
+            port: false,
+        })
+        .collect();
+    diesel::insert_into(functions::table)
+        .values(new_funcs.as_slice())
+        .execute(conn)
+        .expect("error inserting functions");
+    refresh_mat_view(conn);
+}
+
+fn delete_repo(conn: &PgConnection, repo_name: &str) {
+    let repo_ids = repositories::table
+        .filter(repositories::name.eq(repo_name))
+        .select(repositories::id);
+    diesel::delete(functions::table.filter(functions::repo_id.eq_any(repo_ids)))
+        .execute(conn)
+        .expect("error deleting functions");
+    diesel::delete(repositories::table.filter(repositories::name.eq(repo_name)))
+        .execute(conn)
+        .expect("error deleting repo");
+    refresh_mat_view(conn);
+}
+
+#[test]
+#[ignore] // needs a database, run with `cargo test -- --ignored`
+fn text_search_counts_matches_past_the_last_page() {
+    let conn = connection();
+    let repo_name = "fn-search-test/text-search";
+    insert_documented_functions(&conn, repo_name, "zygomorphic", 3);
+    let first = search_function_text(&conn, "zygomorphic", 2, 0).expect("error searching text");
+    let past_end = search_function_text(&conn, "zygomorphic", 2, 10).expect("error searching text");
+    delete_repo(&conn, repo_name);
+    assert_eq!(first.items.len(), 2);
+    assert_eq!(first.total, 3);
+    assert!(past_end.items.is_empty());
+    assert_eq!(past_end.total, 3);
+}
@@ -0,0 +1,167 @@
+use crate::responses::{
+    signature_args, PageResponse, SearchResult, TextSearchResult, TypeResult, API_VERSION,
+};
+use fn_search_backend_db::models::{FunctionTextMatch, FunctionWithRepo, Repository, Type};
+use serde_json::json;
+
+fn test_function() -> FunctionWithRepo {
+    FunctionWithRepo {
+        repo_id: 1,
+        repo_name: String::from("elm/core"),
+        repo_url: String::from("https://github.com/elm/core"),
+        repo_version: String::from("1.0.2"),
+        func_id: 7,
+        func_name: String::from("map"),
+        func_type_sig: String::from("(a -> b) -> List a -> List b"),
+        func_documentation: Some(String::from("Apply a function to every element of a list.")),
+        func_module: String::from("List"),
+    }
+}
+
+#[test]
+fn splits_signature_args() {
+    assert_eq!(
+        signature_args("(a -> b) -> List a -> List b"),
+        vec!["(a -> b)", "List a", "List b"]
+    );
+    assert_eq!(
+        signature_args("Dict comparable v -> Maybe ( comparable, v )"),
+        vec!["Dict comparable v", "Maybe ( comparable, v )"]
+    );
+    assert_eq!(signature_args("Float"), vec!["Float"]);
+    assert_eq!(signature_args("a -> -> b"), vec!["a -> -> b"]);
+}
+
+#[test]
+fn search_result_matches_frontend_decoder() {
+    let res = serde_json::to_value(SearchResult::new(test_function(), None)).unwrap();
+    assert_eq!(
+        res,
+        json!({
+            "repo": {
+                "id": 1,
+                "name": "elm/core",
+                "url": "https://github.com/elm/core",
+                "version": "1.0.2",
+            },
+            "res": {
+                "id": 7,
+                "name": "List.map",
+                "module": "List",
+                "type_signature": "(a -> b) -> List a -> List b",
+                "desc": "Apply a function to every element of a list.",
+                "args": ["(a -> b)", "List a", "List b"],
+            },
+        })
+    );
+}
+
+#[test]
+fn search_result_without_documentation() {
+    let f = FunctionWithRepo {
+        func_documentation: None,
+        ..test_function()
+    };
+    let res = serde_json::to_value(SearchResult::new(f, None)).unwrap();
+    assert_eq!(res["res"]["desc"], json!(""));
+}
+
+#[test]
+fn search_result_without_module() {
+    let f = FunctionWithRepo {
+        func_module: String::new(),
+        ..test_function()
+    };
+    let res = serde_json::to_value(SearchResult::new(f, None)).unwrap();
+    assert_eq!(res["res"]["name"], json!("map"));
+    assert_eq!(res["res"]["module"], json!(""));
+}
+
+#[test]
+fn search_result_wraps_operators() {
+    let f = FunctionWithRepo {
+        func_name: String::from("|>"),
+        func_type_sig: String::from("a -> (a -> b) -> b"),
+        func_module: String::from("Basics"),
+        ..test_function()
+    };
+    let res = serde_json::to_value(SearchResult::new(f, None)).unwrap();
+    assert_eq!(res["res"]["name"], json!("Basics.(|>)"));
+}
+
+#[test]
+fn search_result_includes_score() {
+    let res = serde_json::to_value(SearchResult::new(test_function(), Some(3))).unwrap();
+    assert_eq!(res["res"]["score"], json!(3));
+}
+
+#[test]
+fn text_search_result_includes_snippet() {
+    let res = TextSearchResult::new(FunctionTextMatch {
+        function: test_function(),
+        rank: 0.5,
+        snippet: String::from("Apply a <b>function</b> to every element"),
+    });
+    let res = serde_json::to_value(res).unwrap();
+    assert_eq!(res["res"]["name"], json!("List.map"));
+    assert_eq!(res["repo"]["name"], json!("elm/core"));
+    assert_eq!(
+        res["snippet"],
+        json!("Apply a <b>function</b> to every element")
+    );
+    assert_eq!(res["rank"], json!(0.5));
+}
+
+#[test]
+fn type_result_has_qualified_name() {
+    let t = Type {
+        id: 3,
+        repo_id: 1,
+        name: String::from("Maybe"),
+        module: String::from("Maybe"),
+        kind: String::from("custom"),
+        parameters: vec![String::from("a")],
+        definition: String::from("Just a | Nothing"),
+        constructors: vec![String::from("Just a"), String::from("Nothing")],
+        documentation: None,
+    };
+    let r = Repository {
+        id: 1,
+        name: String::from("elm/core"),
+        url: String::from("https://github.com/elm/core"),
+        ver: String::from("1.0.2"),
+        summary: None,
+        license: None,
+        elm_version: None,
+    };
+    let res = serde_json::to_value(TypeResult::new(t, r)).unwrap();
+    assert_eq!(
+        res["res"],
+        json!({
+            "id": 3,
+            "name": "Maybe.Maybe",
+            "module": "Maybe",
+            "kind": "custom",
+            "parameters": ["a"],
+            "definition": "Just a | Nothing",
+            "constructors": ["Just a", "Nothing"],
+            "desc": "",
+        })
+    );
+    assert_eq!(res["repo"]["version"], json!("1.0.2"));
+}
+
+#[test]
+fn page_response_links_next_page() {
+    assert_eq!(
+        PageResponse::new(vec![1, 2], 5, 2),
+        PageResponse {
+            api_version: API_VERSION,
+            data: vec![1, 2],
+            total: 5,
+            next: Some(4),
+        }
+    );
+    assert_eq!(PageResponse::new(vec![1], 5, 4).next, None);
+    assert_eq!(PageResponse::new(Vec::<i32>::new(), 5, 10).next, None);
+}
@@ -0,0 +1,167 @@
+use crate::search_query::{parse_query, QueryError, SearchQuery};
+
+fn some(s: &str) -> Option<String> {
+    Some(s.to_string())
+}
+
+#[test]
+fn parses_type_signature() {
+    assert_eq!(
+        parse_query("(a -> b) -> List a -> List b"),
+        Ok(SearchQuery {
+            type_signature: some("(a -> b) -> List a -> List b"),
+            ..SearchQuery::default()
+        })
+    );
+}
+
+#[test]
+fn parses_name_type_and_filters() {
+    assert_eq!(
+        parse_query("map : (a -> b) -> List a -> List b package:elm/core module:List"),
+        Ok(SearchQuery {
+            name: some("map"),
+            type_signature: some("(a -> b) -> List a -> List b"),
+            package: some("elm/core"),
+            module: some("List"),
+            ..SearchQuery::default()
+        })
+    );
+}
+
+#[test]
+fn parses_filters_anywhere() {
+    assert_eq!(
+        parse_query("package:elm/core   Int ->   module:Basics Float"),
+        Ok(SearchQuery {
+            type_signature: some("Int -> Float"),
+            package: some("elm/core"),
+            module: some("Basics"),
+            ..SearchQuery::default()
+        })
+    );
+}
+
+#[test]
+fn parses_name_only() {
+    assert_eq!(
+        parse_query("name:foldl package:elm/core"),
+        Ok(SearchQuery {
+            name: some("foldl"),
+            package: some("elm/core"),
+            ..SearchQuery::default()
+        })
+    );
+    assert_eq!(
+        parse_query("foldl :"),
+        Ok(SearchQuery {
+            name: some("foldl"),
+            ..SearchQuery::default()
+        })
+    );
+}
+
+#[test]
+fn ignores_colons_in_records() {
+    assert_eq!(
+        parse_query("{ x:Float, y : Float } -> Float"),
+        Ok(SearchQuery {
+            type_signature: some("{ x:Float, y : Float } -> Float"),
+            ..SearchQuery::default()
+        })
+    );
+}
+
+#[test]
+fn rejects_unknown_filters() {
+    assert_eq!(
+        parse_query("Int -> Int pkg:elm/core"),
+        Err(QueryError::UnknownFilter(String::from("pkg")))
+    );
+    assert_eq!(
+        parse_query("map: a -> a"),
+        Err(QueryError::UnknownFilter(String::from("map")))
+    );
+}
+
+#[test]
+fn rejects_invalid_filters() {
+    assert_eq!(
+        parse_query("Int -> Int package:"),
+        Err(QueryError::MissingValue(String::from("package")))
+    );
+    assert_eq!(
+        parse_query("Int package:elm/core package:elm/json"),
+        Err(QueryError::DuplicateFilter(String::from("package")))
+    );
+    assert_eq!(
+        parse_query("map : a -> a name:map"),
+        Err(QueryError::DuplicateFilter(String::from("name")))
+    );
+}
+
+#[test]
+fn rejects_empty_queries() {
+    assert_eq!(parse_query(""), Err(QueryError::Empty));
+    assert_eq!(parse_query("module:List"), Err(QueryError::Empty));
+}
+
+#[test]
+fn parses_package_filters() {
+    assert_eq!(
+        parse_query("Int -> Int package:elm/* author:elm version:>=1.0.0,<2.0.0"),
+        Ok(SearchQuery {
+            type_signature: some("Int -> Int"),
+            package: some("elm/*"),
+            author: some("elm"),
+            version: ">=1.0.0,<2.0.0".parse().ok(),
+            ..SearchQuery::default()
+        })
+    );
+    assert_eq!(
+        parse_query("Int -> Int version:>=two"),
+        Err(QueryError::InvalidValue(
+            String::from("version"),
+            String::from(">=two")
+        ))
+    );
+}
+
+#[test]
+fn add_filter_rejects_duplicates() {
+    let mut query = parse_query("Int -> Int package:elm/core").unwrap();
+    assert_eq!(query.add_filter("author", "elm"), Ok(()));
+    assert_eq!(
+        query.add_filter("package", "elm/json"),
+        Err(QueryError::DuplicateFilter(String::from("package")))
+    );
+    assert_eq!(
+        query.add_filter("pkg", "elm/json"),
+        Err(QueryError::UnknownFilter(String::from("pkg")))
+    );
+}
+
+#[test]
+fn matches_package_globs() {
+    let query = parse_query("a package:elm/*").unwrap();
+    assert!(query.matches_package("elm/core", "1.0.2"));
+    assert!(query.matches_package("elm/json", "1.1.3"));
+    assert!(!query.matches_package("elm-community/list-extra", "8.2.0"));
+    let query = parse_query("a package:*/list-*").unwrap();
+    assert!(query.matches_package("elm-community/list-extra", "8.2.0"));
+    assert!(!query.matches_package("elm/core", "1.0.2"));
+    let query = parse_query("a package:elm/core").unwrap();
+    assert!(query.matches_package("elm/core", "1.0.2"));
+    assert!(!query.matches_package("elm/core-extra", "1.0.2"));
+}
+
+#[test]
+fn matches_package_author_and_version() {
+    let query = parse_query("a author:elm-community version:>=8.0.0").unwrap();
+    assert!(query.matches_package("elm-community/list-extra", "8.2.0"));
+    assert!(!query.matches_package("elm-community/list-extra", "7.1.0"));
+    assert!(!query.matches_package("elm/core", "8.2.0"));
+    assert!(!query.matches_package("elm-community/list-extra", "master"));
+    assert!(!parse_query("a").unwrap().has_package_filters());
+    assert!(query.has_package_filters());
+}
@@ -0,0 +1,56 @@
+use crate::version::{Version, VersionConstraint};
+
+fn version(v: &str) -> Version {
+    v.parse().expect("error parsing version")
+}
+
+fn constraint(c: &str) -> VersionConstraint {
+    c.parse().expect("error parsing version constraint")
+}
+
+#[test]
+fn parses_versions() {
+    assert_eq!(version("1.0.2"), Version::new(1, 0, 2));
+    assert_eq!(version("2.1"), Version::new(2, 1, 0));
+    assert_eq!(version("3"), Version::new(3, 0, 0));
+    assert!("1.0.0.0".parse::<Version>().is_err());
+    assert!("v1.0.0".parse::<Version>().is_err());
+    assert!("".parse::<Version>().is_err());
+}
+
+#[test]
+fn orders_versions() {
+    assert!(version("1.10.0") > version("1.9.9"));
+    assert!(version("2.0.0") > version("1.99.99"));
+    assert_eq!(version("1.0.0").to_string(), "1.0.0");
+}
+
+#[test]
+fn matches_comparisons() {
+    assert!(constraint(">=2.0.0").matches(&version("2.0.0")));
+    assert!(constraint(">=2.0.0").matches(&version("3.1.0")));
+    assert!(!constraint(">=2.0.0").matches(&version("1.9.0")));
+    assert!(constraint(">1.0.0").matches(&version("1.0.1")));
+    assert!(!constraint(">1.0.0").matches(&version("1.0.0")));
+    assert!(constraint("<=1.0.0").matches(&version("1.0.0")));
+    assert!(constraint("<1.0.0").matches(&version("0.19.1")));
+    assert!(constraint("=1.0.0").matches(&version("1.0.0")));
+    assert!(constraint("1.0.0").matches(&version("1.0.0")));
+    assert!(!constraint("1.0.0").matches(&version("1.0.1")));
+}
+
+#[test]
+fn matches_ranges() {
+    let c = constraint(">=1.0.0, <2.0.0");
+    assert!(c.matches(&version("1.0.0")));
+    assert!(c.matches(&version("1.5.3")));
+    assert!(!c.matches(&version("2.0.0")));
+    assert!(!c.matches(&version("0.9.0")));
+}
+
+#[test]
+fn rejects_invalid_constraints() {
+    assert!(">=".parse::<VersionConstraint>().is_err());
+    assert!(">=1.0.0,".parse::<VersionConstraint>().is_err());
+    assert!("~1.0.0".parse::<VersionConstraint>().is_err());
+}
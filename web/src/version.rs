@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// an elm package version, `MAJOR.MINOR.PATCH`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseVersionError(String);
+
+impl Error for ParseVersionError {}
+
+impl fmt::Display for ParseVersionError {
+    fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> Result<(), fmt::Error> {
+        write!(f, "invalid version `{}`", self.0)
+    }
+}
+
+impl FromStr for Version {
+    type Err = ParseVersionError;
+
+    /// parses a version, missing minor or patch numbers are 0, so `2` is `2.0.0`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseVersionError(s.to_string());
+        let parts = s
+            .split('.')
+            .map(|p| p.parse::<u64>().map_err(|_| err()))
+            .collect::<Result<Vec<u64>, ParseVersionError>>()?;
+        match parts.as_slice() {
+            [major] => Ok(Version::new(*major, 0, 0)),
+            [major, minor] => Ok(Version::new(*major, *minor, 0)),
+            [major, minor, patch] => Ok(Version::new(*major, *minor, *patch)),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt<'a>(&self, f: &mut fmt::Formatter<'a>) -> Result<(), fmt::Error> {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+/// comparison operators, longer operators first so `>=` isn't read as `>`
+const OPERATORS: [(&str, Comparison); 5] = [
+    (">=", Comparison::GreaterOrEqual),
+    ("<=", Comparison::LessOrEqual),
+    (">", Comparison::Greater),
+    ("<", Comparison::Less),
+    ("=", Comparison::Equal),
+];
+
+/// A constraint on versions, such as `>=2.0.0` or `>=1.0.0,<2.0.0`.
+///
+/// a constraint is a comma separated list of comparisons, a version without a comparison
+/// operator must match exactly
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionConstraint(Vec<(Comparison, Version)>);
+
+impl FromStr for VersionConstraint {
+    type Err = ParseVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let comparisons = s
+            .split(',')
+            .map(|c| {
+                let c = c.trim();
+                let (comparison, version) = OPERATORS
+                    .iter()
+                    .find_map(|(op, comparison)| c.strip_prefix(op).map(|v| (*comparison, v)))
+                    .unwrap_or((Comparison::Equal, c));
+                Ok((comparison, version.trim().parse()?))
+            })
+            .collect::<Result<Vec<(Comparison, Version)>, ParseVersionError>>()
+            .map_err(|_| ParseVersionError(s.to_string()))?;
+        Ok(VersionConstraint(comparisons))
+    }
+}
+
+impl VersionConstraint {
+    /// true if version satisfies every comparison in the constraint
+    pub fn matches(&self, version: &Version) -> bool {
+        self.0.iter().all(|(comparison, v)| match comparison {
+            Comparison::Less => version < v,
+            Comparison::LessOrEqual => version <= v,
+            Comparison::Equal => version == v,
+            Comparison::GreaterOrEqual => version >= v,
+            Comparison::Greater => version > v,
+        })
+    }
+}